@@ -0,0 +1,48 @@
+use std::path::Path;
+
+use colored::Colorize;
+use prompted::input;
+use zip_utils::{Error, Package};
+
+/// Make sure the package has been extracted before reading from the extracted folder
+///
+/// When the extracted folder is missing, this asks the user whether to extract it unless `auto_extract` is `true`.
+/// Returns `false` when the user doesn't want to extract, the caller should then read straight from the archive
+pub fn ensure_ooxml_exist(package: &Package, auto_extract: bool) -> Result<bool, Error> {
+    let Package {
+        extracted_folder, ..
+    } = package;
+
+    let output_path = Path::new(extracted_folder);
+
+    if !output_path.exists() {
+        let do_extract = auto_extract
+            || input!(
+                "The extracted folder {} does not exist. \n\tDo you want to extract it? (y/n - default: n): ",
+                extracted_folder
+            )
+            .to_lowercase()
+                == "y";
+
+        if !do_extract {
+            println!(
+                "{}",
+                "Reading straight from the archive without extracting...".yellow()
+            );
+            return Ok(false);
+        }
+
+        // The root folder may hold a hand-edited customXml.json, only the extracted folder is recreated
+        package.restore_extracted_folder()?;
+        println!("{}", "Zip extracted successfully".green());
+    }
+
+    if !output_path.is_dir() {
+        return Err(Error::invalid_package(
+            output_path,
+            "The extracted folder is not a directory",
+        ));
+    }
+
+    Ok(true)
+}
@@ -0,0 +1,108 @@
+use glob::Pattern;
+use std::path::Path;
+
+/// The files the editors, the OS and git write next to the ones being edited, they never lead to a resync or a rezip
+///
+/// Vim swap files, Office and LibreOffice lock files, atomic save temp files and the OS folder metadata
+pub const DEFAULT_IGNORE_GLOBS: &[&str] = &[
+    ".git",
+    ".DS_Store",
+    "Thumbs.db",
+    "desktop.ini",
+    "~$*",
+    ".~lock.*#",
+    "*.swp",
+    "*.swo",
+    "*.swx",
+    "*~",
+    "4913",
+    ".#*",
+    "#*#",
+    "*.tmp",
+    "*.crswap",
+    "*___jb_tmp___",
+    "*___jb_old___",
+];
+
+/// The compiled ignore globs of the watcher
+///
+/// A glob without `/` matches the name of the file or of any folder it's in, e.g. `.git` ignores everything inside it.
+/// A glob with `/` matches the whole path relative to the root folder, e.g. `extracted/docProps/*`.
+#[derive(Debug, Default)]
+pub struct IgnoreGlobs {
+    patterns: Vec<Pattern>,
+}
+
+impl IgnoreGlobs {
+    pub fn new(globs: &[String]) -> Result<Self, String> {
+        let patterns = globs
+            .iter()
+            .map(|glob| compile_glob(glob))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { patterns })
+    }
+
+    /// Whether the path, relative to the root folder, matches one of the globs
+    pub fn is_ignored(&self, relative_path: &Path) -> bool {
+        let components: Vec<String> = relative_path
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy().to_string())
+            .collect();
+        let relative_path = components.join("/");
+
+        self.patterns.iter().any(|pattern| {
+            if pattern.as_str().contains('/') {
+                pattern.matches(&relative_path)
+            } else {
+                components
+                    .iter()
+                    .any(|component| pattern.matches(component))
+            }
+        })
+    }
+}
+
+/// Check that the glob is valid, used to parse the `--ignore` arguments
+pub fn parse_ignore_glob(glob: &str) -> Result<String, String> {
+    compile_glob(glob).map(|_| glob.to_string())
+}
+
+fn compile_glob(glob: &str) -> Result<Pattern, String> {
+    Pattern::new(glob).map_err(|e| format!("Invalid ignore glob {}: {}", glob, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ignore_globs() {
+        let mut globs: Vec<String> = DEFAULT_IGNORE_GLOBS.iter().map(|s| s.to_string()).collect();
+        globs.push("extracted/docProps/*".to_string());
+        let ignore_globs = IgnoreGlobs::new(&globs).unwrap();
+
+        for ignored in [
+            "extracted/word/.document.xml.swp",
+            "extracted/word/document.xml~",
+            "extracted/word/4913",
+            "~$sample.docx",
+            ".DS_Store",
+            ".git/index",
+            "extracted/word/media/.DS_Store",
+            "extracted/docProps/core.xml",
+        ] {
+            assert!(ignore_globs.is_ignored(Path::new(ignored)), "{}", ignored);
+        }
+        for watched in [
+            "customXml.json",
+            "extracted/word/document.xml",
+            "extracted/word/media/image1.png",
+            "extracted/customXml/item1.xml",
+        ] {
+            assert!(!ignore_globs.is_ignored(Path::new(watched)), "{}", watched);
+        }
+
+        assert!(IgnoreGlobs::new(&["[".to_string()]).is_err());
+    }
+}
@@ -0,0 +1,1059 @@
+use crate::cli::{
+    file_watcher::{
+        ignore::{DEFAULT_IGNORE_GLOBS, IgnoreGlobs},
+        self_writes::{SelfWrites, take_snapshot},
+    },
+    input_utils::get_path_from_input::get_extracted_root_folder_path,
+    print_utils::{exit_with_error, print_fn_progress},
+    types::UserPreference,
+    wrappers::sync_custom_xml::print_sync_result,
+};
+use colored::Colorize;
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use prompted::input;
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::mpsc::{self, RecvTimeoutError, Sender},
+    thread,
+    time::Duration,
+};
+use std::{
+    path::{Path, PathBuf},
+    time::Instant,
+};
+use zip_utils::{
+    CUSTOM_XML_FILE_NAME, ContentTypes, CustomXmlFile, CustomXmlInfo, EXTRACTED_FOLDER_NAME, Error,
+    OoxmlPackage, Package, RezipOptions, SUMMARY_FILE_NAME, SyncOptions, ValidationIssue,
+    analyze_custom_xml, analyze_custom_xml_to_file, read_struct_from_json, reextract_zip,
+    rezip_folder, summarize_to_file, sync_custom_xml, validate_folder,
+};
+
+/// How long the root folder has to be quiet before the changes are handled, in milliseconds
+pub const DEFAULT_DEBOUNCE_MS: u64 = 300;
+
+/// Which actions [`watch_folder`] runs without prompting, and how the changes are batched
+#[derive(Debug, Clone)]
+pub struct WatchOptions {
+    /// Rezip as soon as a file in the extracted folder changes
+    pub auto_rezip: bool,
+    /// Resync as soon as customXml.json changes
+    pub auto_resync: bool,
+    /// Re-extract as soon as the packed file changes, e.g. when the document is saved in Word
+    pub auto_reextract: bool,
+    /// The changes are handled together once no file changed for this long
+    pub debounce: Duration,
+    /// The changes of the files matching these globs are ignored, see [`IgnoreGlobs`]
+    pub ignore_globs: Vec<String>,
+}
+
+impl Default for WatchOptions {
+    fn default() -> Self {
+        Self {
+            auto_rezip: false,
+            auto_resync: false,
+            auto_reextract: false,
+            debounce: Duration::from_millis(DEFAULT_DEBOUNCE_MS),
+            ignore_globs: DEFAULT_IGNORE_GLOBS
+                .iter()
+                .map(|glob| glob.to_string())
+                .collect(),
+        }
+    }
+}
+
+/// What a change leads to, in the order they run for a batch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum WatchAction {
+    /// Bring the changes of the packed file into the extracted folder
+    Reextract,
+    /// Sync customXml.json back to the extracted folder
+    Resync,
+    /// Rezip the extracted folder back into the packed file
+    Rezip,
+}
+
+/// Everything the event loop waits for, the watcher events and the answers of the prompts share one channel
+enum WatchMessage {
+    Event(notify::Result<Event>),
+    /// The user answered the prompt of the action, `true` to run it
+    Answer(WatchAction, bool),
+}
+
+/// The actions waiting for a prompt, only one prompt is open at a time
+///
+/// Changes are coalesced: an action is queued once however many events lead to it, and the events leading to the action
+/// whose prompt is open are dropped as it runs on the latest files anyway
+#[derive(Debug, Default)]
+struct ActionQueue {
+    pending: Vec<WatchAction>,
+    prompting: Option<WatchAction>,
+}
+
+impl ActionQueue {
+    /// Queue the action, returns `false` if it's already queued or prompted
+    fn push(&mut self, action: WatchAction) -> bool {
+        if self.prompting == Some(action) || self.pending.contains(&action) {
+            return false;
+        }
+        self.pending.push(action);
+        true
+    }
+
+    /// The next action to prompt for, `None` while a prompt is open or when nothing is queued
+    fn next_prompt(&mut self) -> Option<WatchAction> {
+        if self.prompting.is_some() || self.pending.is_empty() {
+            return None;
+        }
+        let action = self.pending.remove(0);
+        self.prompting = Some(action);
+        Some(action)
+    }
+
+    /// Close the open prompt
+    fn finish_prompt(&mut self) {
+        self.prompting = None;
+    }
+}
+
+/// The files changed since the last batch was handled, a trailing-edge debounce
+///
+/// An editor saving several parts or git checking out a branch fires many events in a row, they're handled as one batch
+/// once the root folder is quiet
+#[derive(Debug, Default)]
+struct ChangeBatch {
+    paths: BTreeSet<PathBuf>,
+    last_change: Option<Instant>,
+}
+
+impl ChangeBatch {
+    fn add(&mut self, path: PathBuf, now: Instant) {
+        self.paths.insert(path);
+        self.last_change = Some(now);
+    }
+
+    /// How long until the batch is due, `None` when there's no change to wait for
+    fn time_until_due(&self, quiet_window: Duration, now: Instant) -> Option<Duration> {
+        self.last_change
+            .map(|last_change| quiet_window.saturating_sub(now.duration_since(last_change)))
+    }
+
+    /// Take the changed files, the next change starts a new batch
+    fn take(&mut self) -> BTreeSet<PathBuf> {
+        self.last_change = None;
+        std::mem::take(&mut self.paths)
+    }
+}
+
+/// The paths [`watch_folder`] works with
+struct WatchContext {
+    root_path: PathBuf,
+    extracted_folder_path: PathBuf,
+    custom_xml_json_path: PathBuf,
+    /// The packed file, it's both rezipped to and re-extracted from
+    output_file_path: String,
+    /// The packed file as the extracted folder was last in sync with it, see [`reextract_zip`]
+    base_package: Option<OoxmlPackage>,
+    /// The files written by the actions, their changes aren't handled again
+    self_writes: SelfWrites,
+}
+
+/// Watch for file changes in the root folder
+pub fn watch_folder_wrapper(user_preference: &mut UserPreference) {
+    println!("\n");
+    let fn_name = "Watch folder";
+    print_fn_progress(fn_name, "Starting file watcher...");
+
+    let root_folder = get_extracted_root_folder_path(user_preference);
+    if !Path::new(&root_folder).exists() {
+        exit_with_error(&Error::invalid_package(
+            &root_folder,
+            "Root folder does not exist",
+        ));
+    }
+
+    let auto_rezip_input =
+        input!("\tRezip without asking when the extracted folder changes? (y/n - default: n): ");
+    let auto_resync_input = input!(
+        "\tResync without asking when {} changes? (y/n - default: n): ",
+        CUSTOM_XML_FILE_NAME
+    );
+    let auto_reextract_input =
+        input!("\tRe-extract without asking when the Word file changes? (y/n - default: n): ");
+    let options = WatchOptions {
+        auto_rezip: auto_rezip_input.to_lowercase() == "y",
+        auto_resync: auto_resync_input.to_lowercase() == "y",
+        auto_reextract: auto_reextract_input.to_lowercase() == "y",
+        ..Default::default()
+    };
+
+    println!("Watching root folder: {}", root_folder);
+
+    let watch_result = watch_folder(&root_folder, &options);
+    if let Err(e) = watch_result {
+        exit_with_error(&e);
+    }
+}
+
+/// Watch for file changes and handle them accordingly
+///
+/// The prompts run on their own thread so the events keep being received, and coalesced, while one is open.
+/// The packed file next to the root folder is watched too, its changes are re-extracted so a save in Word and the
+/// folder stay in sync both ways.
+pub fn watch_folder(root_folder: &str, options: &WatchOptions) -> Result<(), Error> {
+    // * Set up the paths
+    if !Path::new(root_folder).is_dir() {
+        return Err(Error::invalid_package(
+            root_folder,
+            "Root folder is not a directory",
+        ));
+    }
+    // `.` or `..` have no name to give the packed file, the canonical path does
+    let root_path = Path::new(root_folder)
+        .canonicalize()
+        .map_err(|e| Error::io(root_folder, e))?;
+    let (Some(root_folder_name), Some(packed_file_folder)) =
+        (root_path.file_name(), root_path.parent())
+    else {
+        return Err(Error::invalid_package(
+            &root_path,
+            "Root folder has no name or no parent folder",
+        ));
+    };
+    let ignore_globs = IgnoreGlobs::new(&options.ignore_globs)
+        .map_err(|reason| Error::invalid_package(&root_path, reason))?;
+
+    let extracted_folder_path = root_path.join(EXTRACTED_FOLDER_NAME);
+    if !extracted_folder_path.is_dir() {
+        return Err(Error::invalid_package(
+            &root_path,
+            "Missing extracted folder",
+        ));
+    }
+
+    let custom_xml_json_path = root_path.join(CUSTOM_XML_FILE_NAME);
+    let has_custom_xml_file = custom_xml_json_path.is_file();
+    if !has_custom_xml_file {
+        println!(
+            "\n{}",
+            format!(
+                "customXml file {} not found, ignoring...",
+                CUSTOM_XML_FILE_NAME
+            )
+            .yellow()
+        );
+    }
+
+    let extension = ContentTypes::read_from_folder(&extracted_folder_path)?.extension();
+    let file_name = format!("{}.{}", root_folder_name.to_string_lossy(), extension);
+    let output_file_path = packed_file_folder
+        .join(file_name)
+        .to_string_lossy()
+        .to_string();
+
+    let trigger_label = |is_auto: bool, action: &str| {
+        if is_auto {
+            format!("will {} automatically", action)
+        } else {
+            format!("will trigger {} prompt", action)
+        }
+    };
+    println!("\n{}", "File watcher started. Press Ctrl+C to stop.".blue());
+    println!("{}", "Watching for changes in:".yellow());
+    if has_custom_xml_file {
+        println!(
+            "\t- {} ({})",
+            CUSTOM_XML_FILE_NAME,
+            trigger_label(options.auto_resync, "resync")
+        );
+    }
+    println!(
+        "\t- {} folder ({})",
+        EXTRACTED_FOLDER_NAME,
+        trigger_label(options.auto_rezip, "rezip")
+    );
+    println!(
+        "\t- {} ({})",
+        output_file_path,
+        trigger_label(options.auto_reextract, "re-extract")
+    );
+    println!();
+
+    let mut context = WatchContext {
+        root_path: root_path.clone(),
+        extracted_folder_path,
+        custom_xml_json_path,
+        // The folder is assumed to be extracted from the packed file as it is now, edits made before are unsaved edits
+        base_package: OoxmlPackage::open(&output_file_path).ok(),
+        output_file_path,
+        self_writes: SelfWrites::default(),
+    };
+
+    // * Set up the watcher
+    // https://docs.rs/notify/latest/notify/index.html#examples
+    let (tx, rx) = mpsc::channel();
+    let event_tx = tx.clone();
+    // Create a watcher with recommended backend to make sure it works on all platforms
+    let mut watcher = notify::recommended_watcher(move |res| {
+        let _ = event_tx.send(WatchMessage::Event(res));
+    })
+    .map_err(|e| Error::io(&root_path, std::io::Error::other(e)))?;
+    // Watch the root folder recursively
+    watcher
+        .watch(&root_path, RecursiveMode::Recursive)
+        .map_err(|e| Error::io(&root_path, std::io::Error::other(e)))?;
+    // Word saves by replacing the packed file, so its folder is watched rather than the file itself
+    watcher
+        .watch(packed_file_folder, RecursiveMode::NonRecursive)
+        .map_err(|e| Error::io(packed_file_folder, std::io::Error::other(e)))?;
+
+    // Editors and git fire several events per file and change several files at once, the changes are handled in
+    //  batches once the root folder is quiet, our re-save functions take the latest content anyway
+    let normalized_root_path = normalized_path(&root_path);
+    let mut change_batch = ChangeBatch::default();
+    let mut action_queue = ActionQueue::default();
+
+    // * Handle events
+    // This will run forever until the program is stopped
+    loop {
+        let message = match change_batch.time_until_due(options.debounce, Instant::now()) {
+            None => match rx.recv() {
+                Ok(message) => Some(message),
+                Err(_) => break,
+            },
+            Some(timeout) => match rx.recv_timeout(timeout) {
+                Ok(message) => Some(message),
+                Err(RecvTimeoutError::Timeout) => None,
+                Err(RecvTimeoutError::Disconnected) => break,
+            },
+        };
+
+        let mut did_execute_action = false;
+
+        match message {
+            // The root folder is quiet, handle the batch
+            None => {
+                let mut changed_paths = change_batch.take();
+                // The events of our own resync, rezip or re-extract
+                changed_paths.retain(|path| !context.self_writes.is_own_write(path));
+                if changed_paths.is_empty() {
+                    continue;
+                }
+                print_batch_summary(&changed_paths, &context);
+                warn_removed_parts_still_referenced(&changed_paths, &context);
+
+                let actions: BTreeSet<WatchAction> = changed_paths
+                    .iter()
+                    .filter_map(|path| get_watch_action(path, &context))
+                    // Our own rezip doesn't need to be re-extracted, a removed customXml.json can't be resynced
+                    .filter(|action| match action {
+                        WatchAction::Reextract => is_packed_file_changed(&context),
+                        WatchAction::Resync => context.custom_xml_json_path.is_file(),
+                        WatchAction::Rezip => true,
+                    })
+                    .collect();
+                // Re-extract first so the resync and the rezip work on the latest files
+                for action in actions {
+                    did_execute_action |=
+                        schedule_action(action, options, &mut context, &mut action_queue);
+                }
+            }
+            Some(WatchMessage::Event(Err(e))) => {
+                println!("{}", format!("Watcher error: {}", e).red());
+                continue;
+            }
+            Some(WatchMessage::Event(Ok(Event { kind, paths, .. })))
+                if !paths
+                    .iter()
+                    .any(|path| normalized_path(path).starts_with(&normalized_root_path)) =>
+            {
+                // The other files next to the packed file, like the lock file of Word, aren't watched
+                let packed_file_paths = paths
+                    .into_iter()
+                    .filter(|path| is_packed_file(path, &context));
+                if matches!(kind, EventKind::Modify(_) | EventKind::Create(_)) {
+                    for path in packed_file_paths {
+                        change_batch.add(path, Instant::now());
+                    }
+                }
+            }
+            Some(WatchMessage::Event(Ok(Event { kind, paths, .. }))) => match kind {
+                // Renaming is a modification of the name, both the old and the new paths are in the batch
+                EventKind::Modify(_) | EventKind::Create(_) | EventKind::Remove(_) => {
+                    // Swap, lock and temp files of the editors, the OS metadata files
+                    let watched_paths = paths.into_iter().filter(|path| {
+                        !is_ignored_path(path, &normalized_root_path, &ignore_globs)
+                    });
+                    for path in watched_paths {
+                        change_batch.add(path, Instant::now());
+                    }
+                }
+                // Opening and closing a file doesn't change it
+                EventKind::Access(_) => {}
+                _ => {
+                    // ! Other event types are ignored
+                    println!("{}", format!("Unsupported event type: {:?}", kind).yellow());
+                }
+            },
+            Some(WatchMessage::Answer(action, confirmed)) => {
+                action_queue.finish_prompt();
+                if confirmed {
+                    let did_change_extracted = run_action(action, &mut context);
+                    if action == WatchAction::Resync && did_change_extracted {
+                        schedule_action(
+                            WatchAction::Rezip,
+                            options,
+                            &mut context,
+                            &mut action_queue,
+                        );
+                    }
+                } else {
+                    match action {
+                        WatchAction::Reextract => {
+                            println!("{}", "Re-extract cancelled.".yellow())
+                        }
+                        WatchAction::Resync => println!("{}", "Resync cancelled.".yellow()),
+                        WatchAction::Rezip => println!("{}", "Rezip cancelled.".yellow()),
+                    }
+                }
+                did_execute_action = true;
+            }
+        }
+
+        if let Some(action) = action_queue.next_prompt() {
+            spawn_prompt(action, tx.clone());
+        } else if did_execute_action && action_queue.prompting.is_none() {
+            println!("\n{}\n", "Watching for changes...".blue());
+        }
+    }
+
+    Ok(())
+}
+
+/// Run the action right away in auto mode or queue its prompt, returns whether it ran
+///
+/// A resync that changed the extracted folder is followed by a rezip, so each batch is one sync-then-rezip cycle
+fn schedule_action(
+    action: WatchAction,
+    options: &WatchOptions,
+    context: &mut WatchContext,
+    action_queue: &mut ActionQueue,
+) -> bool {
+    let is_auto = match action {
+        WatchAction::Reextract => options.auto_reextract,
+        WatchAction::Resync => options.auto_resync,
+        WatchAction::Rezip => options.auto_rezip,
+    };
+    if !is_auto {
+        action_queue.push(action);
+        return false;
+    }
+
+    let did_change_extracted = run_action(action, context);
+    if action == WatchAction::Resync && did_change_extracted {
+        schedule_action(WatchAction::Rezip, options, context, action_queue);
+    }
+    true
+}
+
+/// The action a change of the file leads to, `None` if the file isn't watched
+fn get_watch_action(changed_path: &Path, context: &WatchContext) -> Option<WatchAction> {
+    // * Normalize paths for comparison
+    let normalized_changed = normalized_path(changed_path);
+
+    if is_packed_file(changed_path, context) {
+        return Some(WatchAction::Reextract);
+    }
+    if normalized_changed == normalized_path(&context.custom_xml_json_path) {
+        return Some(WatchAction::Resync);
+    }
+    if normalized_changed.starts_with(normalized_path(&context.extracted_folder_path)) {
+        return Some(WatchAction::Rezip);
+    }
+
+    None
+}
+
+/// Whether the path inside the root folder matches one of the ignore globs
+fn is_ignored_path(path: &Path, normalized_root_path: &Path, ignore_globs: &IgnoreGlobs) -> bool {
+    normalized_path(path)
+        .strip_prefix(normalized_root_path)
+        .is_ok_and(|relative_path| ignore_globs.is_ignored(relative_path))
+}
+
+fn is_packed_file(path: &Path, context: &WatchContext) -> bool {
+    normalized_path(path) == normalized_path(Path::new(&context.output_file_path))
+}
+
+/// Whether the packed file differs from the one the extracted folder was last in sync with
+fn is_packed_file_changed(context: &WatchContext) -> bool {
+    let Ok(package) = OoxmlPackage::open(&context.output_file_path) else {
+        // It's being written or was removed, the next change will tell
+        return false;
+    };
+    match &context.base_package {
+        Some(base_package) => {
+            base_package.parts().len() != package.parts().len()
+                || base_package
+                    .parts()
+                    .iter()
+                    .any(|part| package.read_part(&part.name) != Some(part.data.as_slice()))
+        }
+        None => true,
+    }
+}
+
+/// Print the files of the batch relative to the root folder, grouped by what they lead to
+fn print_batch_summary(changed_paths: &BTreeSet<PathBuf>, context: &WatchContext) {
+    let normalized_root = normalized_path(&context.root_path);
+    let get_relative_path = |path: &PathBuf| {
+        let normalized = normalized_path(path);
+        normalized
+            .strip_prefix(&normalized_root)
+            .map(|relative_path| relative_path.display().to_string())
+            .unwrap_or_else(|_| path.display().to_string())
+    };
+
+    println!(
+        "{}",
+        format!("{} file(s) changed:", changed_paths.len()).bright_blue()
+    );
+    for path in changed_paths {
+        let label = match get_watch_action(path, context) {
+            Some(WatchAction::Reextract) => "packed file changed".to_string(),
+            Some(WatchAction::Resync) => format!("{} changed", CUSTOM_XML_FILE_NAME),
+            Some(WatchAction::Rezip) => format!("{} folder changed", EXTRACTED_FOLDER_NAME),
+            None => "not supported".to_string(),
+        };
+        let label = if path.exists() {
+            label
+        } else {
+            format!("{}, removed", label)
+        };
+        println!("\t- {} ({})", get_relative_path(path), label.yellow());
+    }
+}
+
+/// Warn about the removed parts of the extracted folder that are still the target of a relationship
+///
+/// The package would point to a missing part once rezipped, see [`ValidationIssue::MissingRelationshipTarget`]
+fn warn_removed_parts_still_referenced(changed_paths: &BTreeSet<PathBuf>, context: &WatchContext) {
+    for (target_part_name, rels_part_name, id) in
+        find_relationships_to_removed_parts(changed_paths, context)
+    {
+        println!(
+            "{}",
+            format!(
+                "⚠ {} was removed but {} still points to it ({})",
+                target_part_name, rels_part_name, id
+            )
+            .yellow()
+        );
+    }
+}
+
+/// The relationships whose target is one of the removed paths, as `(target part name, .rels part name, id)`
+fn find_relationships_to_removed_parts(
+    changed_paths: &BTreeSet<PathBuf>,
+    context: &WatchContext,
+) -> Vec<(String, String, String)> {
+    let normalized_extracted = normalized_path(&context.extracted_folder_path);
+    let removed_part_names: Vec<String> = changed_paths
+        .iter()
+        .filter(|path| !path.exists())
+        .filter_map(|path| {
+            let relative_path = normalized_path(path)
+                .strip_prefix(&normalized_extracted)
+                .ok()?
+                .to_path_buf();
+            let part_name = relative_path
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            Some(part_name.to_lowercase())
+        })
+        .collect();
+    if removed_part_names.is_empty() {
+        return Vec::new();
+    }
+
+    let Ok(report) = validate_folder(&context.extracted_folder_path.to_string_lossy()) else {
+        return Vec::new();
+    };
+    let mut relationships = Vec::new();
+    for issue in report.issues {
+        let ValidationIssue::MissingRelationshipTarget {
+            rels_part_name,
+            id,
+            target,
+        } = issue
+        else {
+            continue;
+        };
+        let target_key = target.to_lowercase();
+        // A removed folder takes the parts inside it along
+        let is_removed = removed_part_names.iter().any(|part_name| {
+            target_key == *part_name || target_key.starts_with(&format!("{}/", part_name))
+        });
+        if is_removed {
+            relationships.push((target, rels_part_name, id));
+        }
+    }
+
+    relationships
+}
+
+/// Ask whether to run the action on another thread, the answer is sent back to the event loop
+fn spawn_prompt(action: WatchAction, tx: Sender<WatchMessage>) {
+    thread::spawn(move || {
+        let response = match action {
+            WatchAction::Reextract => input!("Do you want to re-extract? (y/n - default: y): "),
+            WatchAction::Resync => input!("Do you want to resync? (y/n - default: y): "),
+            WatchAction::Rezip => input!("Do you want to rezip? (y/n - default: y): "),
+        };
+        let _ = tx.send(WatchMessage::Answer(action, response.to_lowercase() != "n"));
+    });
+}
+
+/// Re-extract the packed file, resync the custom XML or rezip the extracted folder, returns whether files of the extracted
+/// folder were written
+///
+/// The files the action wrote are recorded in [`SelfWrites`] so their events don't lead to another action
+fn run_action(action: WatchAction, context: &mut WatchContext) -> bool {
+    let output_file_path = PathBuf::from(&context.output_file_path);
+    let before = take_snapshot(&context.root_path, &[&output_file_path]);
+    let did_change_extracted = run_action_without_tracking(action, context);
+    let after = take_snapshot(&context.root_path, &[&output_file_path]);
+    context.self_writes.record(&before, &after);
+
+    did_change_extracted
+}
+
+fn run_action_without_tracking(action: WatchAction, context: &mut WatchContext) -> bool {
+    match action {
+        WatchAction::Reextract => reextract_packed_file(context),
+        WatchAction::Resync => {
+            let root_folder = context.root_path.to_string_lossy().to_string();
+            match sync_custom_xml(&root_folder, &SyncOptions::default()) {
+                Ok(sync_result) => {
+                    print_sync_result(&sync_result);
+                    println!("{}", "Resync completed successfully!".green());
+                    !(sync_result.synced_files.is_empty()
+                        && sync_result.created_files.is_empty()
+                        && sync_result.deleted_files.is_empty())
+                }
+                Err(e) => {
+                    println!("{}", format!("Resync failed: {}", e).red());
+                    false
+                }
+            }
+        }
+        WatchAction::Rezip => {
+            let extracted_folder_path_str =
+                context.extracted_folder_path.to_string_lossy().to_string();
+            println!(
+                "Rezipping from {} to {}...",
+                extracted_folder_path_str, context.output_file_path
+            );
+
+            let result = RezipOptions::for_extracted_folder(&extracted_folder_path_str).and_then(
+                |options| {
+                    rezip_folder(
+                        &extracted_folder_path_str,
+                        &context.output_file_path,
+                        &options,
+                    )
+                },
+            );
+            match result {
+                Ok(_) => {
+                    context.base_package = OoxmlPackage::open(&context.output_file_path).ok();
+                    println!("{}", "Rezip completed successfully!".green());
+                }
+                Err(e) => {
+                    println!("{}", format!("Rezip failed: {}", e).red());
+                }
+            }
+            false
+        }
+    }
+}
+
+/// Bring the changes of the packed file into the extracted folder and analyze it again, see [`reextract_zip`]
+///
+/// Nothing is written when the folder or customXml.json have edits that would be lost
+fn reextract_packed_file(context: &mut WatchContext) -> bool {
+    let extracted_folder = context.extracted_folder_path.to_string_lossy().to_string();
+    println!(
+        "Re-extracting {} to {}...",
+        context.output_file_path, extracted_folder
+    );
+
+    let Some(base_package) = &context.base_package else {
+        println!(
+            "{}",
+            format!(
+                "Re-extract skipped: {} didn't exist when the watcher started, unsaved edits of the {} folder can't be told apart. Run `extract --force` to take it as is.",
+                context.output_file_path, EXTRACTED_FOLDER_NAME
+            )
+            .red()
+        );
+        return false;
+    };
+    if has_unsynced_custom_xml(context) {
+        println!(
+            "{}",
+            format!(
+                "Re-extract skipped: {} has edits that aren't synced yet, resync them first",
+                CUSTOM_XML_FILE_NAME
+            )
+            .red()
+        );
+        return false;
+    }
+
+    let result = OoxmlPackage::open(&context.output_file_path).and_then(|package| {
+        reextract_zip(base_package, &package, &extracted_folder).map(|result| (package, result))
+    });
+    let (package, result) = match result {
+        Ok(result) => result,
+        Err(e) => {
+            println!("{}", format!("Re-extract failed: {}", e).red());
+            return false;
+        }
+    };
+
+    if !result.conflicts.is_empty() {
+        println!(
+            "{}",
+            format!(
+                "Re-extract skipped: {} part(s) changed both in the packed file and in the {} folder:",
+                result.conflicts.len(),
+                EXTRACTED_FOLDER_NAME
+            )
+            .red()
+        );
+        for part_name in &result.conflicts {
+            println!("\t- {}", part_name);
+        }
+        println!(
+            "{}",
+            "Rezip to keep the folder's edits, or run `extract --force` to take the packed file's"
+                .yellow()
+        );
+        return false;
+    }
+
+    for part_name in &result.updated_parts {
+        println!("Updated: {}", part_name);
+    }
+    for part_name in &result.added_parts {
+        println!("Added: {}", part_name);
+    }
+    for part_name in &result.removed_parts {
+        println!("Removed: {}", part_name);
+    }
+    context.base_package = Some(package);
+
+    // * Refresh the analysis files that were generated before
+    if result.has_changes()
+        && let Err(e) = reanalyze(context)
+    {
+        println!("{}", format!("Analyze failed: {}", e).red());
+    }
+
+    println!("{}", "Re-extract completed successfully!".green());
+    result.has_changes()
+}
+
+/// Whether customXml.json differs from the custom XML of the extracted folder, re-analyzing would overwrite it
+fn has_unsynced_custom_xml(context: &WatchContext) -> bool {
+    if !context.custom_xml_json_path.is_file() {
+        return false;
+    }
+
+    let custom_xml_files = read_struct_from_json::<Vec<CustomXmlFile>>(
+        &context.custom_xml_json_path.to_string_lossy(),
+    );
+    let analysis = analyze_custom_xml(&context.extracted_folder_path.to_string_lossy());
+    let (Ok(custom_xml_files), Ok(analysis)) = (custom_xml_files, analysis) else {
+        return true;
+    };
+
+    // The entries are matched like sync does, the analysis follows the order of the folder rather than customXml.json's
+    let extracted_infos: HashMap<&str, &CustomXmlInfo> = analysis
+        .custom_xml_files
+        .iter()
+        .map(|extracted| (get_custom_xml_key(extracted), &extracted.custom_xml_info))
+        .collect();
+    custom_xml_files.len() != analysis.custom_xml_files.len()
+        || custom_xml_files.iter().any(|edited| {
+            extracted_infos.get(get_custom_xml_key(edited)) != Some(&&edited.custom_xml_info)
+        })
+}
+
+/// The datastore item ID of the custom XML file, or its file name when it doesn't have item props
+fn get_custom_xml_key(custom_xml_file: &CustomXmlFile) -> &str {
+    match &custom_xml_file.item_props {
+        Some(item_props) => &item_props.item_id,
+        None => &custom_xml_file.file_info.file_name_with_extension,
+    }
+}
+
+/// Write customXml.json and summary.json again if they exist
+fn reanalyze(context: &WatchContext) -> Result<(), Error> {
+    let package = Package::open(&context.output_file_path)?;
+    if context.custom_xml_json_path.is_file() {
+        analyze_custom_xml_to_file(&package)?;
+        println!("Analyzed: {}", CUSTOM_XML_FILE_NAME);
+    }
+    if context.root_path.join(SUMMARY_FILE_NAME).is_file() {
+        summarize_to_file(&package)?;
+        println!("Summarized: {}", SUMMARY_FILE_NAME);
+    }
+
+    Ok(())
+}
+
+/// Normalize a path for comparison (handle Windows/Unix path differences)
+/// This is used to make sure the changed path is the same as the last used path
+///
+/// A removed file can't be canonicalized, its folder is so the path still compares to the canonical root folder when it
+/// sits behind a symlink or contains `..`
+pub fn normalized_path(path: &Path) -> PathBuf {
+    if let Ok(path) = path.canonicalize() {
+        return path;
+    }
+    match (path.parent().map(Path::canonicalize), path.file_name()) {
+        (Some(Ok(parent)), Some(file_name)) => parent.join(file_name),
+        _ => path.to_path_buf(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::types::FIXTURE_FOLDER_PATH;
+
+    use super::*;
+    use std::env::temp_dir;
+    use std::fs::File;
+    use std::io::Write;
+
+    #[test]
+    fn test_normalized_path_with_existing_file() {
+        // Create a temporary file
+        let mut temp_file = temp_dir();
+        temp_file.push(FIXTURE_FOLDER_PATH);
+        temp_file.push("test_normalize.txt");
+
+        let mut file = File::create(&temp_file).expect("Failed to create temp file");
+        file.write_all(b"test").expect("Failed to write");
+        drop(file);
+
+        // Test normalization of existing file
+        let normalized = normalized_path(&temp_file);
+
+        // Should return a canonicalized path (absolute)
+        assert!(normalized.is_absolute());
+
+        // Clean up
+        let _ = std::fs::remove_file(&temp_file);
+    }
+
+    #[test]
+    fn test_normalized_path_with_nonexistent_file() {
+        // Test with a non-existent path
+        let mut nonexistent_path = temp_dir();
+        nonexistent_path.push(FIXTURE_FOLDER_PATH);
+        nonexistent_path.push("nonexistent/path/file.txt");
+        let normalized = normalized_path(Path::new(&nonexistent_path));
+
+        // Should return the original path as PathBuf when canonicalize fails
+        assert_eq!(normalized, nonexistent_path.to_path_buf());
+    }
+
+    #[test]
+    fn test_normalized_path_with_removed_file() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(temp_dir.path().join("extracted")).unwrap();
+        let canonical_root = temp_dir.path().canonicalize().unwrap();
+
+        // The file doesn't exist anymore but its folder does, e.g. the path of a Remove event
+        let removed_path = temp_dir.path().join("extracted/../customXml.json");
+        let normalized = normalized_path(&removed_path);
+
+        assert_eq!(normalized, canonical_root.join(CUSTOM_XML_FILE_NAME));
+        assert!(normalized.starts_with(&canonical_root));
+    }
+
+    #[test]
+    fn test_normalized_path_with_relative_path() {
+        // Create a temporary file in current directory for relative path test
+        let mut temp_file = temp_dir();
+        temp_file.push(FIXTURE_FOLDER_PATH);
+        temp_file.push("test_normalize_relative.txt");
+        let mut file = File::create(&temp_file).expect("Failed to create temp file");
+        file.write_all(b"test").expect("Failed to write");
+        drop(file);
+
+        // Test with relative path
+        let relative_path = Path::new(&temp_file);
+        let normalized = normalized_path(relative_path);
+
+        // Should return an absolute path when file exists
+        assert!(normalized.is_absolute());
+
+        // Clean up
+        let _ = std::fs::remove_file(&temp_file);
+    }
+
+    #[test]
+    fn test_normalized_path_with_directory() {
+        // Create a temporary directory
+        let mut temp_dir = temp_dir();
+        temp_dir.push(FIXTURE_FOLDER_PATH);
+        temp_dir.push("test_normalize_dir");
+        std::fs::create_dir_all(&temp_dir).expect("Failed to create temp dir");
+
+        // Test normalization of directory
+        let normalized = normalized_path(&temp_dir);
+
+        // Should return a canonicalized path (absolute)
+        assert!(normalized.is_absolute());
+        assert!(normalized.is_dir());
+
+        // Clean up
+        let _ = std::fs::remove_dir_all(&temp_dir);
+    }
+
+    #[test]
+    fn test_action_queue_coalesces_changes() {
+        let mut action_queue = ActionQueue::default();
+
+        assert!(action_queue.push(WatchAction::Rezip));
+        assert!(!action_queue.push(WatchAction::Rezip));
+        assert_eq!(action_queue.next_prompt(), Some(WatchAction::Rezip));
+
+        // The changes while the prompt is open are coalesced into it, other actions wait for it to close
+        assert!(!action_queue.push(WatchAction::Rezip));
+        assert!(action_queue.push(WatchAction::Resync));
+        assert!(!action_queue.push(WatchAction::Resync));
+        assert_eq!(action_queue.next_prompt(), None);
+
+        action_queue.finish_prompt();
+        assert_eq!(action_queue.next_prompt(), Some(WatchAction::Resync));
+        action_queue.finish_prompt();
+        assert_eq!(action_queue.next_prompt(), None);
+    }
+
+    #[test]
+    fn test_change_batch_is_due_after_quiet_window() {
+        let quiet_window = Duration::from_millis(300);
+        let start = Instant::now();
+        let mut change_batch = ChangeBatch::default();
+        assert_eq!(change_batch.time_until_due(quiet_window, start), None);
+
+        change_batch.add(PathBuf::from("extracted/word/document.xml"), start);
+        change_batch.add(
+            PathBuf::from("extracted/word/styles.xml"),
+            start + Duration::from_millis(200),
+        );
+        change_batch.add(
+            PathBuf::from("extracted/word/document.xml"),
+            start + Duration::from_millis(250),
+        );
+        // Every change restarts the quiet window
+        assert_eq!(
+            change_batch.time_until_due(quiet_window, start + Duration::from_millis(300)),
+            Some(Duration::from_millis(250))
+        );
+        assert_eq!(
+            change_batch.time_until_due(quiet_window, start + Duration::from_secs(1)),
+            Some(Duration::ZERO)
+        );
+
+        assert_eq!(change_batch.take().len(), 2);
+        assert_eq!(change_batch.time_until_due(quiet_window, start), None);
+    }
+
+    #[test]
+    fn test_find_relationships_to_removed_parts() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let extracted_folder_path = temp_dir.path().join(EXTRACTED_FOLDER_NAME);
+        std::fs::create_dir_all(extracted_folder_path.join("word/_rels")).unwrap();
+        std::fs::write(
+            extracted_folder_path.join("word/_rels/document.xml.rels"),
+            r#"<Relationships><Relationship Id="rId1" Type="image" Target="media/image1.png"/><Relationship Id="rId2" Type="styles" Target="styles.xml"/></Relationships>"#,
+        )
+        .unwrap();
+        let context = WatchContext {
+            root_path: temp_dir.path().to_path_buf(),
+            custom_xml_json_path: temp_dir.path().join(CUSTOM_XML_FILE_NAME),
+            output_file_path: String::new(),
+            base_package: None,
+            extracted_folder_path: extracted_folder_path.clone(),
+            self_writes: SelfWrites::default(),
+        };
+
+        // The styles are missing too but they weren't removed in this batch
+        let changed_paths = BTreeSet::from([extracted_folder_path.join("word/media")]);
+        assert_eq!(
+            find_relationships_to_removed_parts(&changed_paths, &context),
+            vec![(
+                "word/media/image1.png".to_string(),
+                "word/_rels/document.xml.rels".to_string(),
+                "rId1".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_has_unsynced_custom_xml_matches_entries_by_item_id() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let extracted_folder_path = temp_dir.path().join(EXTRACTED_FOLDER_NAME);
+        let custom_xml_folder = extracted_folder_path.join("customXml");
+        std::fs::create_dir_all(&custom_xml_folder).unwrap();
+        for (index, item_id) in [(1, "{A}"), (2, "{B}")] {
+            std::fs::write(
+                custom_xml_folder.join(format!("item{}.xml", index)),
+                format!(r#"<root>{{"item":{}}}</root>"#, index),
+            )
+            .unwrap();
+            std::fs::write(
+                custom_xml_folder.join(format!("itemProps{}.xml", index)),
+                format!(
+                    r#"<ds:datastoreItem ds:itemID="{}" xmlns:ds="urn:ds"/>"#,
+                    item_id
+                ),
+            )
+            .unwrap();
+        }
+        let context = WatchContext {
+            root_path: temp_dir.path().to_path_buf(),
+            custom_xml_json_path: temp_dir.path().join(CUSTOM_XML_FILE_NAME),
+            output_file_path: String::new(),
+            base_package: None,
+            extracted_folder_path: extracted_folder_path.clone(),
+            self_writes: SelfWrites::default(),
+        };
+        let mut custom_xml_files = analyze_custom_xml(&extracted_folder_path.to_string_lossy())
+            .unwrap()
+            .custom_xml_files;
+        let write_custom_xml_json = |custom_xml_files: &Vec<CustomXmlFile>| {
+            zip_utils::write_struct_to_json(
+                custom_xml_files,
+                &context.custom_xml_json_path.to_string_lossy(),
+            )
+            .unwrap()
+        };
+
+        // The order of customXml.json doesn't matter
+        custom_xml_files.reverse();
+        write_custom_xml_json(&custom_xml_files);
+        assert!(!has_unsynced_custom_xml(&context));
+
+        custom_xml_files[0].custom_xml_info.json_content["item"] = serde_json::json!(3);
+        write_custom_xml_json(&custom_xml_files);
+        assert!(has_unsynced_custom_xml(&context));
+    }
+}
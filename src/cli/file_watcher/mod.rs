@@ -0,0 +1,3 @@
+pub mod ignore;
+pub mod main;
+pub mod self_writes;
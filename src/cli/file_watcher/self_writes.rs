@@ -0,0 +1,120 @@
+use std::{
+    collections::{BTreeMap, hash_map::DefaultHasher},
+    fs,
+    hash::{Hash, Hasher},
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use crate::cli::file_watcher::main::normalized_path;
+
+/// The size and modification time of each file, to tell which ones an action wrote without reading them all
+pub type FileSnapshot = BTreeMap<PathBuf, (u64, Option<SystemTime>)>;
+
+/// The files the watcher wrote itself, so their events don't lead to another action
+///
+/// A resync rewrites `item*.xml` in the extracted folder and a re-extract rewrites parts, customXml.json and summary.json,
+/// their events would prompt for a rezip or a resync again. The files an action wrote are fingerprinted, and their
+/// events are dropped as long as they still have the content the watcher wrote.
+#[derive(Debug, Default)]
+pub struct SelfWrites {
+    fingerprints: BTreeMap<PathBuf, Option<u64>>,
+}
+
+impl SelfWrites {
+    /// Record the files written or removed between the two snapshots
+    pub fn record(&mut self, before: &FileSnapshot, after: &FileSnapshot) {
+        for path in before.keys().chain(after.keys()) {
+            if before.get(path) != after.get(path) {
+                self.fingerprints
+                    .insert(path.clone(), get_fingerprint(path));
+            }
+        }
+    }
+
+    /// Whether the file still has the content the watcher wrote, its change is then the watcher's own
+    ///
+    /// The record is dropped once the file has another content, the next change is the user's
+    pub fn is_own_write(&mut self, path: &Path) -> bool {
+        let path = normalized_path(path);
+        match self.fingerprints.get(&path) {
+            Some(fingerprint) if *fingerprint == get_fingerprint(&path) => true,
+            Some(_) => {
+                self.fingerprints.remove(&path);
+                false
+            }
+            None => false,
+        }
+    }
+}
+
+/// Snapshot the files of the folder and the other given files
+pub fn take_snapshot(folder: &Path, files: &[&Path]) -> FileSnapshot {
+    let mut snapshot = FileSnapshot::new();
+    add_to_snapshot(&mut snapshot, folder);
+    for file in files {
+        add_to_snapshot(&mut snapshot, file);
+    }
+
+    snapshot
+}
+
+fn add_to_snapshot(snapshot: &mut FileSnapshot, path: &Path) {
+    let Ok(metadata) = fs::metadata(path) else {
+        return;
+    };
+    if metadata.is_dir() {
+        for entry in fs::read_dir(path).into_iter().flatten().flatten() {
+            add_to_snapshot(snapshot, &entry.path());
+        }
+    } else {
+        snapshot.insert(
+            normalized_path(path),
+            (metadata.len(), metadata.modified().ok()),
+        );
+    }
+}
+
+/// The hash of the file's content, `None` when it doesn't exist
+fn get_fingerprint(path: &Path) -> Option<u64> {
+    let data = fs::read(path).ok()?;
+    let mut hasher = DefaultHasher::new();
+    data.hash(&mut hasher);
+    Some(hasher.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_self_writes_only_suppress_own_content() {
+        let temp_dir = tempdir().unwrap();
+        let item_path = temp_dir.path().join("item1.xml");
+        let removed_path = temp_dir.path().join("item2.xml");
+        let untouched_path = temp_dir.path().join("document.xml");
+        fs::write(&item_path, "<root>1</root>").unwrap();
+        fs::write(&removed_path, "<root>2</root>").unwrap();
+        fs::write(&untouched_path, "<document/>").unwrap();
+
+        let mut self_writes = SelfWrites::default();
+        let before = take_snapshot(temp_dir.path(), &[]);
+        // What a resync writes
+        fs::write(&item_path, "<root>10</root>").unwrap();
+        fs::remove_file(&removed_path).unwrap();
+        self_writes.record(&before, &take_snapshot(temp_dir.path(), &[]));
+
+        assert!(self_writes.is_own_write(&item_path));
+        assert!(self_writes.is_own_write(&removed_path));
+        assert!(!self_writes.is_own_write(&untouched_path));
+        // Still the watcher's content when its events come in several batches
+        assert!(self_writes.is_own_write(&item_path));
+
+        // Edited by the user since
+        fs::write(&item_path, "<root>11</root>").unwrap();
+        assert!(!self_writes.is_own_write(&item_path));
+        fs::write(&item_path, "<root>10</root>").unwrap();
+        assert!(!self_writes.is_own_write(&item_path));
+    }
+}
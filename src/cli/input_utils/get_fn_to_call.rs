@@ -1,10 +1,14 @@
-use crate::utils::{
-    analyze_custom_xml::main::analyze_custom_xml_wrapper,
+use crate::cli::{
     file_watcher::main::watch_folder_wrapper,
-    summarize::main::summarize_wrapper,
-    sync_custom_xml::main::sync_custom_xml_wrapper,
     types::UserPreference,
-    zip_utils::main::{extract_zip_wrapper, rezip_folder_wrapper},
+    wrappers::{
+        analyze_custom_xml::analyze_custom_xml_wrapper,
+        archive::{extract_zip_wrapper, rezip_folder_wrapper},
+        data_bindings::analyze_data_bindings_wrapper,
+        summarize::summarize_wrapper,
+        sync_custom_xml::sync_custom_xml_wrapper,
+        validate::validate_wrapper,
+    },
 };
 use colored::Colorize;
 use prompted::input;
@@ -108,9 +112,29 @@ pub fn get_fn_to_call() -> Result<(), &'static str> {
         "→".bright_blue()
     );
     println!();
+    println!(
+        "  {}  {}",
+        "7.".bright_cyan().bold(),
+        "Validate".bright_white().bold()
+    );
+    println!(
+        "      {} Check the content types and relationships of the package",
+        "→".bright_blue()
+    );
+    println!();
+    println!(
+        "  {}  {}",
+        "8.".bright_cyan().bold(),
+        "Analyze data bindings".bright_white().bold()
+    );
+    println!(
+        "      {} List the content controls bound to custom XML data",
+        "→".bright_blue()
+    );
+    println!();
 
     let prompt_text = format!(
-        "{} Select feature (1-6) {} [last used: {}]: ",
+        "{} Select feature (1-8) {} [last used: {}]: ",
         "┌─".bright_cyan(),
         "─┐".bright_cyan(),
         user_preference.last_used_feature
@@ -130,6 +154,8 @@ pub fn get_fn_to_call() -> Result<(), &'static str> {
         "4" => analyze_custom_xml_wrapper(&mut user_preference),
         "5" => sync_custom_xml_wrapper(&mut user_preference),
         "6" => watch_folder_wrapper(&mut user_preference),
+        "7" => validate_wrapper(&mut user_preference),
+        "8" => analyze_data_bindings_wrapper(&mut user_preference),
         _ => return Err("Invalid feature"),
     }
 
@@ -1,10 +1,12 @@
 use colored::Colorize;
 use prompted::input;
 
-use crate::utils::types::{FilePathInfo, UserPreference};
+use zip_utils::Package;
+
+use crate::cli::{print_utils::exit_with_error, types::UserPreference};
 
 /// Get the file path from the input or use preference
-pub fn get_file_path_from_input(user_preference: &mut UserPreference) -> FilePathInfo {
+pub fn get_file_path_from_input(user_preference: &mut UserPreference) -> Package {
     let last_input_path = user_preference.clone().last_used_file_path;
 
     let prompt_text = format!(
@@ -21,7 +23,10 @@ pub fn get_file_path_from_input(user_preference: &mut UserPreference) -> FilePat
         user_preference.save_last_used_file_path(input_path.clone());
     }
 
-    FilePathInfo::new(input_path)
+    match Package::open(&input_path) {
+        Ok(package) => package,
+        Err(e) => exit_with_error(&e),
+    }
 }
 
 // * Rezip stuff
@@ -0,0 +1,359 @@
+use clap::{Parser, Subcommand, ValueEnum};
+use colored::Colorize;
+use std::{path::Path, time::Duration};
+
+use zip_utils::{
+    Compression, Error, OoxmlPackage, Package, RezipOptions, SchemaTarget, SyncOptions,
+    analyze_custom_xml_archive_to_file, analyze_custom_xml_to_file, analyze_data_bindings,
+    analyze_data_bindings_in_archive, register_custom_xml_schema, rezip_folder,
+    summarize_archive_to_file, summarize_to_file, sync_custom_xml, validate_archive,
+    validate_folder,
+};
+
+use crate::cli::{
+    ensure_ooxml_exist::ensure_ooxml_exist,
+    file_watcher::{
+        ignore::parse_ignore_glob,
+        main::{DEFAULT_DEBOUNCE_MS, WatchOptions, watch_folder},
+    },
+    print_utils::print_fn_progress,
+    wrappers::{
+        analyze_custom_xml::print_custom_xml_analysis, data_bindings::print_data_binding_analysis,
+        sync_custom_xml::print_sync_result, validate::print_validation_report,
+    },
+};
+
+/// Extract Word files into their OOXML representation and edit the internal structure
+///
+/// Run without a subcommand to use the interactive menu
+#[derive(Parser)]
+#[command(version, about)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Extract a Word file into its OOXML representation
+    Extract {
+        /// Path to the Word file
+        file_path: String,
+        /// Override the output root folder if it already exists
+        #[arg(long)]
+        force: bool,
+    },
+    /// Re-zip an extracted folder back into a Word file
+    Rezip {
+        /// Path to the extracted folder
+        folder_path: String,
+        /// Path to the output file that will be created
+        output_file_path: String,
+        /// How every part is compressed, the original compression of manifest.json is kept when omitted
+        #[arg(long, value_enum)]
+        compression: Option<CompressionMethod>,
+        /// Deflate level (0-9) of every part, implies `--compression deflated`
+        #[arg(long, value_parser = clap::value_parser!(i64).range(0..=9))]
+        compression_level: Option<i64>,
+        /// Don't restore the original entry order and compression from manifest.json
+        #[arg(long)]
+        ignore_manifest: bool,
+        /// Refuse to rezip when the folder has validation issues
+        #[arg(long)]
+        validate: bool,
+        /// Rezip even if some .xml or .rels parts are not well-formed
+        #[arg(long)]
+        force: bool,
+    },
+    /// Analyze and summarize the file structure into summary.json
+    Summarize {
+        /// Path to the Word file, it will be extracted if needed
+        file_path: String,
+        /// Read straight from the archive instead of the extracted folder
+        #[arg(long)]
+        from_archive: bool,
+    },
+    /// Analyze the custom XML files into customXml.json
+    AnalyzeCustomXml {
+        /// Path to the Word file, it will be extracted if needed
+        file_path: String,
+        /// Read straight from the archive instead of the extracted folder
+        #[arg(long)]
+        from_archive: bool,
+    },
+    /// List the content controls bound to custom XML data, with the values they resolve to
+    AnalyzeDataBindings {
+        /// Path to the Word file or to the extracted folder
+        path: String,
+    },
+    /// Validate the content types and relationships of a package
+    Validate {
+        /// Path to the Word file or to the extracted folder
+        path: String,
+    },
+    /// Sync customXml.json back to the custom XML files in the extracted folder
+    SyncCustomXml {
+        /// Path to the root folder containing the extracted folder and customXml.json
+        root_folder: String,
+        /// Delete the custom XML parts that were removed from customXml.json
+        #[arg(long)]
+        delete_removed: bool,
+        /// Only print what would be created, updated, skipped or deleted, with the changes of each update
+        #[arg(long)]
+        dry_run: bool,
+        /// Rewrite the text of the content controls bound to the synced custom XML data
+        #[arg(long)]
+        refresh_data_bindings: bool,
+    },
+    /// Register the JSON Schema the custom XML data has to match before it's synced
+    RegisterSchema {
+        /// Path to the root folder containing customXml.json
+        root_folder: String,
+        /// Path to the JSON Schema file, relative to the root folder
+        schema_path: String,
+        /// The tag of the custom XML root or element the schema applies to
+        #[arg(
+            long,
+            required_unless_present = "schema_ref",
+            conflicts_with = "schema_ref"
+        )]
+        tag: Option<String>,
+        /// The item props schema reference the schema applies to
+        #[arg(long)]
+        schema_ref: Option<String>,
+    },
+    /// Watch the root folder for changes and prompt to resync or rezip
+    Watch {
+        /// Path to the root folder containing the extracted folder
+        root_folder: String,
+        /// Rezip without prompting when a file in the extracted folder changes
+        #[arg(long)]
+        auto_rezip: bool,
+        /// Resync without prompting when customXml.json changes
+        #[arg(long)]
+        auto_resync: bool,
+        /// Re-extract without prompting when the packed file changes
+        #[arg(long)]
+        auto_reextract: bool,
+        /// How long the folder has to be quiet before the changed files are handled together
+        #[arg(long, default_value_t = DEFAULT_DEBOUNCE_MS)]
+        debounce_ms: u64,
+        /// Ignore the changes of the files matching the glob, relative to the root folder (repeatable)
+        #[arg(long = "ignore", value_name = "GLOB", value_parser = parse_ignore_glob)]
+        ignore_globs: Vec<String>,
+        /// Don't ignore the swap, lock and temp files of the editors by default
+        #[arg(long)]
+        no_default_ignores: bool,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum CompressionMethod {
+    /// Store the parts as is
+    Stored,
+    /// Deflate the parts
+    Deflated,
+}
+
+/// Run a subcommand without any interactive prompt for the paths
+pub fn run_command(command: Command) -> Result<(), Error> {
+    match command {
+        Command::Extract { file_path, force } => {
+            let fn_name = "Extract zip";
+            print_fn_progress(fn_name, "Extracting zip...");
+
+            Package::open(&file_path)?.extract(force)?;
+
+            print_fn_progress(
+                fn_name,
+                "Zip extraction finished!".green().to_string().as_str(),
+            );
+        }
+        Command::Rezip {
+            folder_path,
+            output_file_path,
+            compression,
+            compression_level,
+            ignore_manifest,
+            validate,
+            force,
+        } => {
+            let fn_name = "Rezip folder";
+            print_fn_progress(fn_name, "Rezipping folder...");
+
+            let mut options = if ignore_manifest {
+                RezipOptions::default()
+            } else {
+                RezipOptions::for_extracted_folder(&folder_path)?
+            };
+            options.compression = match (compression, compression_level) {
+                (Some(CompressionMethod::Stored), _) => Some(Compression::Stored),
+                (Some(CompressionMethod::Deflated), level) | (None, level @ Some(_)) => {
+                    Some(Compression::Deflated(level))
+                }
+                (None, None) => None,
+            };
+            options.validate = validate;
+            options.skip_xml_check = force;
+            rezip_folder(&folder_path, &output_file_path, &options)?;
+
+            print_fn_progress(fn_name, "Rezipping finished!".green().to_string().as_str());
+        }
+        Command::Summarize {
+            file_path,
+            from_archive,
+        } => {
+            let fn_name = "Summarize";
+            print_fn_progress(fn_name, "Summarizing...");
+
+            let package = Package::open(&file_path)?;
+            if from_archive {
+                summarize_archive_to_file(&package)?;
+            } else {
+                ensure_ooxml_exist(&package, true)?;
+                summarize_to_file(&package)?;
+            }
+
+            print_fn_progress(
+                fn_name,
+                &format!("Summary file: {}", package.summary_file_path()),
+            );
+        }
+        Command::AnalyzeCustomXml {
+            file_path,
+            from_archive,
+        } => {
+            let fn_name = "Analyze customXML";
+            print_fn_progress(fn_name, "Analyzing customXML...");
+
+            let package = Package::open(&file_path)?;
+            let analysis = if from_archive {
+                analyze_custom_xml_archive_to_file(&package)?
+            } else {
+                ensure_ooxml_exist(&package, true)?;
+                analyze_custom_xml_to_file(&package)?
+            };
+            print_custom_xml_analysis(&analysis);
+
+            print_fn_progress(
+                fn_name,
+                &format!(
+                    "Custom XML info file written at path: {}",
+                    package.custom_xml_file_path()
+                ),
+            );
+        }
+        Command::AnalyzeDataBindings { path } => {
+            let fn_name = "Analyze data bindings";
+            print_fn_progress(fn_name, "Analyzing data bindings...");
+
+            let analysis = if Path::new(&path).is_dir() {
+                analyze_data_bindings(&path)?
+            } else {
+                analyze_data_bindings_in_archive(&OoxmlPackage::open(&path)?)?
+            };
+            print_data_binding_analysis(&analysis);
+
+            print_fn_progress(
+                fn_name,
+                "Analyzing data bindings completed successfully!"
+                    .green()
+                    .to_string()
+                    .as_str(),
+            );
+        }
+        Command::Validate { path } => {
+            let fn_name = "Validate";
+            print_fn_progress(fn_name, "Validating...");
+
+            let report = if Path::new(&path).is_dir() {
+                validate_folder(&path)?
+            } else {
+                validate_archive(&OoxmlPackage::open(&path)?)?
+            };
+            print_validation_report(report)?;
+
+            print_fn_progress(fn_name, "Validation finished!".green().to_string().as_str());
+        }
+        Command::SyncCustomXml {
+            root_folder,
+            delete_removed,
+            dry_run,
+            refresh_data_bindings,
+        } => {
+            let fn_name = "Sync customXML";
+            print_fn_progress(fn_name, "Syncing customXML...");
+
+            let options = SyncOptions {
+                delete_removed,
+                dry_run,
+                refresh_data_bindings,
+            };
+            print_sync_result(&sync_custom_xml(&root_folder, &options)?);
+
+            print_fn_progress(
+                fn_name,
+                "Syncing customXML completed successfully!"
+                    .green()
+                    .to_string()
+                    .as_str(),
+            );
+        }
+        Command::RegisterSchema {
+            root_folder,
+            schema_path,
+            tag,
+            schema_ref,
+        } => {
+            let fn_name = "Register schema";
+            print_fn_progress(fn_name, "Registering JSON Schema...");
+
+            let target = match (tag, schema_ref) {
+                (Some(tag), _) => SchemaTarget::Tag(tag),
+                (None, Some(schema_ref)) => SchemaTarget::SchemaRef(schema_ref),
+                (None, None) => unreachable!("clap requires --tag or --schema-ref"),
+            };
+            register_custom_xml_schema(&root_folder, &target, schema_path.into())?;
+
+            print_fn_progress(
+                fn_name,
+                "JSON Schema registered successfully!"
+                    .green()
+                    .to_string()
+                    .as_str(),
+            );
+        }
+        Command::Watch {
+            root_folder,
+            auto_rezip,
+            auto_resync,
+            auto_reextract,
+            debounce_ms,
+            ignore_globs,
+            no_default_ignores,
+        } => {
+            print_fn_progress("Watch folder", "Starting file watcher...");
+            if !Path::new(&root_folder).exists() {
+                return Err(Error::invalid_package(
+                    &root_folder,
+                    "Root folder does not exist",
+                ));
+            }
+
+            let mut options = WatchOptions {
+                auto_rezip,
+                auto_resync,
+                auto_reextract,
+                debounce: Duration::from_millis(debounce_ms),
+                ..Default::default()
+            };
+            if no_default_ignores {
+                options.ignore_globs.clear();
+            }
+            options.ignore_globs.extend(ignore_globs);
+            watch_folder(&root_folder, &options)?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,7 @@
+pub mod ensure_ooxml_exist;
+pub mod file_watcher;
+pub mod input_utils;
+pub mod main;
+pub mod print_utils;
+pub mod types;
+pub mod wrappers;
@@ -0,0 +1,87 @@
+use colored::Colorize;
+use zip_utils::Error;
+
+/// Exit code for failures that aren't about a package, e.g. an invalid menu option
+pub const GENERIC_EXIT_CODE: i32 = 1;
+
+pub fn get_error_message(message: &str) -> String {
+    let error_icon = "✗".red().bold();
+    let error_label = "ERROR".red().bold();
+    format!("{} {} {}", error_icon, error_label, message.red())
+}
+
+pub fn print_error_and_exit(message: &str, exit_code: i32) -> ! {
+    eprintln!("{}", get_error_message(message));
+    std::process::exit(exit_code);
+}
+
+/// Print the error and exit with the exit code matching the kind of error
+pub fn exit_with_error(error: &Error) -> ! {
+    print_error_and_exit(&error.to_string(), get_exit_code(error));
+}
+
+/// Map the error to its exit code, following the BSD `sysexits.h` convention
+pub fn get_exit_code(error: &Error) -> i32 {
+    match error {
+        // EX_DATAERR
+        Error::Zip { .. }
+        | Error::Json { .. }
+        | Error::CustomXmlParse { .. }
+        | Error::XmlSyntax { .. }
+        | Error::Validation { .. }
+        | Error::SchemaValidation { .. } => 65,
+        // EX_NOINPUT
+        Error::InvalidPackage { .. } => 66,
+        // EX_IOERR
+        Error::Io { .. } => 74,
+    }
+}
+
+pub fn print_fn_progress(fn_name: &str, message: &str) {
+    let fn_name_formatted = format!("[{}]", fn_name).bright_cyan().bold();
+    let arrow = "→".bright_blue();
+    let message_formatted = message.bright_white();
+    println!("{} {} {}", fn_name_formatted, arrow, message_formatted);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io;
+
+    #[test]
+    fn test_get_exit_code() {
+        let io_error = Error::io("a.docx", io::Error::from(io::ErrorKind::NotFound));
+        assert_eq!(get_exit_code(&io_error), 74);
+
+        let invalid_package = Error::invalid_package("a/extracted", "missing _rels");
+        assert_eq!(get_exit_code(&invalid_package), 66);
+
+        let custom_xml_parse = Error::custom_xml_parse("item1.xml", "invalid JSON");
+        assert_eq!(get_exit_code(&custom_xml_parse), 65);
+
+        let json_error = serde_json::from_str::<serde_json::Value>("{").unwrap_err();
+        assert_eq!(
+            get_exit_code(&Error::json("customXml.json", json_error)),
+            65
+        );
+
+        let validation = Error::Validation {
+            path: "a/extracted".into(),
+            issues: Vec::new(),
+        };
+        assert_eq!(get_exit_code(&validation), 65);
+
+        let schema_validation = Error::SchemaValidation {
+            path: "customXml.json".into(),
+            violations: Vec::new(),
+        };
+        assert_eq!(get_exit_code(&schema_validation), 65);
+
+        let xml_syntax = Error::xml_syntax("word/document.xml", 3, 1, "Missing end tag");
+        assert_eq!(get_exit_code(&xml_syntax), 65);
+
+        // Failures should never map to the success exit code
+        assert_ne!(GENERIC_EXIT_CODE, 0);
+    }
+}
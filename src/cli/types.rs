@@ -0,0 +1,72 @@
+use serde::{Deserialize, Serialize};
+use zip_utils::{read_struct_from_json, write_struct_to_json};
+
+/// The path to the preference file that store user's last used params
+const PREFERENCE_FILE_PATH: &str = "preference.json";
+
+/// The path to the fixture folder
+#[cfg(test)]
+pub const FIXTURE_FOLDER_PATH: &str = ".local";
+
+/// The user preference that stores the last used feature and file path
+///
+/// ! Note that this will automatically save to file when the last used feature, file path or folder path is changed
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct UserPreference {
+    pub last_used_feature: String,
+    pub last_used_file_path: String,
+    /// Folder containing the extracted files
+    pub last_used_extracted_folder_path: String,
+    /// The path to the output file that will be created
+    pub last_used_output_file_path: String,
+    /// The path to the root folder that contains the extracted and other files such as customXml.json and summary.json
+    pub last_used_root_folder_path: String,
+}
+
+impl UserPreference {
+    pub fn new() -> Self {
+        match read_struct_from_json::<UserPreference>(PREFERENCE_FILE_PATH) {
+            Ok(user_preference) => user_preference,
+            Err(_) => {
+                let user_preference = Self {
+                    last_used_feature: "N/A".to_string(),
+                    last_used_file_path: "N/A".to_string(),
+                    last_used_extracted_folder_path: "N/A".to_string(),
+                    last_used_output_file_path: "N/A".to_string(),
+                    last_used_root_folder_path: "N/A".to_string(),
+                };
+                user_preference.save_to_file();
+                user_preference
+            }
+        }
+    }
+
+    pub fn save_to_file(&self) {
+        let _ = write_struct_to_json(self, PREFERENCE_FILE_PATH);
+    }
+
+    pub fn save_last_used_file_path(&mut self, file_path: String) {
+        self.last_used_file_path = file_path;
+        self.save_to_file()
+    }
+
+    pub fn save_last_used_folder_path(&mut self, folder_path: String) {
+        self.last_used_extracted_folder_path = folder_path;
+        self.save_to_file()
+    }
+
+    pub fn save_last_used_feature(&mut self, feature: String) {
+        self.last_used_feature = feature;
+        self.save_to_file()
+    }
+
+    pub fn save_last_used_output_file_path(&mut self, last_used_output_file_path: String) {
+        self.last_used_output_file_path = last_used_output_file_path;
+        self.save_to_file()
+    }
+
+    pub fn save_last_used_root_folder_path(&mut self, root_folder_path: String) {
+        self.last_used_root_folder_path = root_folder_path;
+        self.save_to_file()
+    }
+}
@@ -0,0 +1,72 @@
+use colored::Colorize;
+use zip_utils::{
+    CustomXmlAnalysis, analyze_custom_xml_archive_to_file, analyze_custom_xml_to_file,
+};
+
+use crate::cli::{
+    ensure_ooxml_exist::ensure_ooxml_exist,
+    input_utils::get_path_from_input::get_file_path_from_input,
+    print_utils::{exit_with_error, print_fn_progress},
+    types::UserPreference,
+};
+
+/// Analyze the custom XMLs in the extracted folder and write them to customXml.json
+pub fn analyze_custom_xml_wrapper(user_preference: &mut UserPreference) {
+    println!("\n");
+    let fn_name = "Analyze customXML";
+    print_fn_progress(fn_name, "Analyzing customXML...");
+
+    let package = get_file_path_from_input(user_preference);
+    package.file_path_info.print_info();
+
+    let analyze_result = match ensure_ooxml_exist(&package, false) {
+        Ok(true) => analyze_custom_xml_to_file(&package),
+        Ok(false) => analyze_custom_xml_archive_to_file(&package),
+        Err(e) => Err(e),
+    };
+    match analyze_result {
+        Ok(analysis) => print_custom_xml_analysis(&analysis),
+        Err(e) => exit_with_error(&e),
+    }
+
+    println!(
+        "Custom XML info file written at path: {}",
+        package.custom_xml_file_path()
+    );
+    print_fn_progress(
+        fn_name,
+        "Analyzing customXML completed successfully!"
+            .green()
+            .to_string()
+            .as_str(),
+    );
+}
+
+/// Print which custom XML files were parsed and which were skipped
+pub fn print_custom_xml_analysis(analysis: &CustomXmlAnalysis) {
+    for custom_xml_file in &analysis.custom_xml_files {
+        println!(
+            "{}",
+            format!(
+                "Parsed custom XML content for file: {}",
+                custom_xml_file.file_info.file_name_with_extension
+            )
+            .green()
+        );
+    }
+
+    for error in &analysis.skipped_files {
+        println!("{}", error.to_string().yellow());
+    }
+
+    let parsed_file_count = analysis.custom_xml_files.len();
+    println!(
+        "\n{}\n",
+        format!(
+            "Parsed {} custom XML files out of {} total files",
+            parsed_file_count,
+            parsed_file_count + analysis.skipped_files.len()
+        )
+        .green()
+    );
+}
@@ -0,0 +1,90 @@
+use crate::cli::{
+    input_utils::get_path_from_input::{
+        get_file_path_from_input, get_folder_path_from_input_for_rezip,
+        get_output_file_path_from_input_for_rezip,
+    },
+    print_utils::{exit_with_error, print_fn_progress},
+    types::UserPreference,
+};
+use colored::Colorize;
+use prompted::input;
+use std::path::Path;
+use zip_utils::{Error, RezipOptions, rezip_folder};
+
+// * --- Unzip
+/// Extract the zip file into a new folder
+pub fn extract_zip_wrapper(user_preference: &mut UserPreference) {
+    println!("\n");
+    let fn_name = "Extract zip";
+    print_fn_progress(fn_name, "Extracting zip...");
+
+    let package = get_file_path_from_input(user_preference);
+    package.file_path_info.print_info();
+
+    // Ask before overriding the previous extraction
+    if Path::new(&package.root_folder).exists() {
+        println!(
+            "{}",
+            "The output root folder already exists... skipping folder creation".yellow()
+        );
+
+        let override_input = input!("\tOverride? (y/n - default: n): ");
+        if override_input.to_lowercase() != "y" {
+            println!("{}", "Operation cancelled".yellow());
+            return;
+        }
+    }
+
+    println!("Creating the output folder...");
+    if let Err(e) = package.extract(true) {
+        exit_with_error(&e);
+    }
+    println!("{}", "Zip extracted successfully".green());
+
+    print_fn_progress(
+        fn_name,
+        "Zip extraction finished!\n".green().to_string().as_str(),
+    );
+}
+
+// * --- Rezip
+/// Rezip an extracted folder into a Word file
+pub fn rezip_folder_wrapper(user_preference: &mut UserPreference) {
+    println!("\n");
+    let fn_name = "Rezip folder";
+    print_fn_progress(fn_name, "Rezipping folder...");
+
+    let folder_path = get_folder_path_from_input_for_rezip(user_preference);
+    let output_file_path = get_output_file_path_from_input_for_rezip(user_preference);
+    println!("\tExtracted folder path: {}", folder_path);
+    println!("\tOutput file path: {}", output_file_path);
+
+    println!(
+        "Creating the zip file from {} to {}...",
+        folder_path, output_file_path
+    );
+    let mut options = match RezipOptions::for_extracted_folder(&folder_path) {
+        Ok(options) => options,
+        Err(e) => exit_with_error(&e),
+    };
+    let mut result = rezip_folder(&folder_path, &output_file_path, &options);
+
+    // Let the user decide whether a malformed part should still be packed
+    if let Err(e @ Error::XmlSyntax { .. }) = &result {
+        println!("{}", e.to_string().red());
+        let force_input = input!("\tRezip anyway? (y/n - default: n): ");
+        if force_input.to_lowercase() != "y" {
+            println!("{}", "Operation cancelled".yellow());
+            return;
+        }
+
+        options.skip_xml_check = true;
+        result = rezip_folder(&folder_path, &output_file_path, &options);
+    }
+    if let Err(e) = result {
+        exit_with_error(&e);
+    }
+    println!("{}", "Zip file created successfully".green());
+
+    print_fn_progress(fn_name, "Rezipping finished!".green().to_string().as_str());
+}
@@ -0,0 +1,98 @@
+use colored::Colorize;
+use zip_utils::{DataBindingAnalysis, analyze_data_bindings_in_archive};
+
+use crate::cli::{
+    ensure_ooxml_exist::ensure_ooxml_exist,
+    input_utils::get_path_from_input::get_file_path_from_input,
+    print_utils::{exit_with_error, print_fn_progress},
+    types::UserPreference,
+};
+
+/// List the content controls of the Word file that are bound to custom XML data
+pub fn analyze_data_bindings_wrapper(user_preference: &mut UserPreference) {
+    println!("\n");
+    let fn_name = "Analyze data bindings";
+    print_fn_progress(fn_name, "Analyzing data bindings...");
+
+    let package = get_file_path_from_input(user_preference);
+    package.file_path_info.print_info();
+
+    let analyze_result = match ensure_ooxml_exist(&package, false) {
+        Ok(true) => package.analyze_data_bindings(),
+        Ok(false) => package
+            .load()
+            .and_then(|package| analyze_data_bindings_in_archive(&package)),
+        Err(e) => Err(e),
+    };
+    match analyze_result {
+        Ok(analysis) => print_data_binding_analysis(&analysis),
+        Err(e) => exit_with_error(&e),
+    }
+
+    print_fn_progress(
+        fn_name,
+        "Analyzing data bindings completed successfully!"
+            .green()
+            .to_string()
+            .as_str(),
+    );
+}
+
+/// Print each bound content control with the custom XML value it resolves to
+///
+/// A value that differs from the text the content control shows is highlighted, Word updates it the next time it opens
+/// the document
+pub fn print_data_binding_analysis(analysis: &DataBindingAnalysis) {
+    for data_binding in &analysis.data_bindings {
+        let name = match (&data_binding.tag, &data_binding.alias) {
+            (Some(tag), Some(alias)) if tag != alias => format!("{} ({})", tag, alias),
+            (Some(name), _) | (None, Some(name)) => name.clone(),
+            (None, None) => "(untitled)".to_string(),
+        };
+        println!("{}", format!("{}: {}", data_binding.part_name, name).bold());
+        println!(
+            "\t{} {}",
+            data_binding
+                .custom_xml_part_name
+                .as_deref()
+                .unwrap_or("(no custom XML part)"),
+            data_binding.xpath
+        );
+
+        match (&data_binding.error, &data_binding.value) {
+            (Some(error), _) => println!("\t{}", error.red()),
+            (None, Some(value)) if *value == data_binding.text => {
+                println!("\tValue: {:?}", value)
+            }
+            (None, Some(value)) => println!(
+                "\t{}",
+                format!(
+                    "Value: {:?} (the document shows {:?})",
+                    value, data_binding.text
+                )
+                .yellow()
+            ),
+            (None, None) => println!(
+                "\t{}",
+                format!(
+                    "The XPath doesn't select anything (the document shows {:?})",
+                    data_binding.text
+                )
+                .yellow()
+            ),
+        }
+    }
+
+    for error in &analysis.skipped_parts {
+        println!("{}", error.to_string().yellow());
+    }
+
+    println!(
+        "\n{}\n",
+        format!(
+            "Found {} bound content controls",
+            analysis.data_bindings.len()
+        )
+        .green()
+    );
+}
@@ -0,0 +1,6 @@
+pub mod analyze_custom_xml;
+pub mod archive;
+pub mod data_bindings;
+pub mod summarize;
+pub mod sync_custom_xml;
+pub mod validate;
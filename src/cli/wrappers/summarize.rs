@@ -0,0 +1,32 @@
+use colored::Colorize;
+use zip_utils::{summarize_archive_to_file, summarize_to_file};
+
+use crate::cli::{
+    ensure_ooxml_exist::ensure_ooxml_exist,
+    input_utils::get_path_from_input::get_file_path_from_input,
+    print_utils::{exit_with_error, print_fn_progress},
+    types::UserPreference,
+};
+
+/// Summarize the structure of the Word file
+pub fn summarize_wrapper(user_preference: &mut UserPreference) {
+    println!("\n");
+    let fn_name = "Summarize";
+    print_fn_progress(fn_name, "Summarizing...");
+
+    let package = get_file_path_from_input(user_preference);
+    package.file_path_info.print_info();
+
+    let summarize_result = match ensure_ooxml_exist(&package, false) {
+        Ok(true) => summarize_to_file(&package),
+        Ok(false) => summarize_archive_to_file(&package),
+        Err(e) => Err(e),
+    };
+    if let Err(e) = summarize_result {
+        exit_with_error(&e);
+    }
+
+    println!("Summary file: {}", package.summary_file_path());
+
+    println!("{}", "Summarization completed successfully!".green());
+}
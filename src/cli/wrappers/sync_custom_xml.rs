@@ -0,0 +1,107 @@
+use colored::Colorize;
+use prompted::input;
+use zip_utils::{SyncOptions, SyncResult, sync_custom_xml};
+
+use crate::cli::{
+    input_utils::get_path_from_input::get_extracted_root_folder_path,
+    print_utils::{exit_with_error, get_error_message, print_fn_progress},
+    types::UserPreference,
+};
+
+/// Sync the customXml.json back to the customXml folder
+pub fn sync_custom_xml_wrapper(user_preference: &mut UserPreference) {
+    println!("\n");
+    let fn_name = "Sync customXML";
+    print_fn_progress(fn_name, "Syncing customXML...");
+
+    let root_folder = get_extracted_root_folder_path(user_preference);
+    println!("Root folder: {}", root_folder);
+
+    let delete_input =
+        input!("\tDelete the custom XML files removed from customXml.json? (y/n - default: n): ");
+    let dry_run_input = input!("\tPreview the changes only (dry run)? (y/n - default: n): ");
+    let refresh_input = input!(
+        "\tRewrite the text of the content controls bound to the custom XML? (y/n - default: n): "
+    );
+    let options = SyncOptions {
+        delete_removed: delete_input.to_lowercase() == "y",
+        dry_run: dry_run_input.to_lowercase() == "y",
+        refresh_data_bindings: refresh_input.to_lowercase() == "y",
+    };
+
+    match sync_custom_xml(&root_folder, &options) {
+        Ok(sync_result) => print_sync_result(&sync_result),
+        Err(e) => exit_with_error(&e),
+    }
+
+    println!("{}", "Syncing customXML completed successfully!".green());
+}
+
+/// Print what happened to each custom XML file during the sync, or what would happen to it for a dry run
+pub fn print_sync_result(sync_result: &SyncResult) {
+    let (synced_label, created_label, deleted_label) = if sync_result.dry_run {
+        ("Would update", "Would create", "Would delete")
+    } else {
+        ("Synced", "Created", "Deleted")
+    };
+
+    for file_name in &sync_result.synced_files {
+        println!("{}: {}", synced_label, file_name);
+        let changes = sync_result
+            .diffs
+            .iter()
+            .find(|diff| diff.file_name == *file_name)
+            .map(|diff| diff.changes.as_slice())
+            .unwrap_or_default();
+        for change in changes {
+            println!("\t{}", change);
+        }
+    }
+    for file_name in &sync_result.created_files {
+        println!("{}: {}", created_label, file_name);
+    }
+    for file_name in &sync_result.deleted_files {
+        println!("{}: {}", deleted_label, file_name);
+    }
+    for file_name in &sync_result.skipped_files {
+        println!("Skipped (unchanged): {}", file_name);
+    }
+    for data_binding in &sync_result.refreshed_data_bindings {
+        println!(
+            "Refreshed content control {} in {}: {:?} → {:?}",
+            data_binding
+                .tag
+                .as_deref()
+                .or(data_binding.alias.as_deref())
+                .unwrap_or(&data_binding.xpath),
+            data_binding.part_name,
+            data_binding.text,
+            data_binding.value.as_deref().unwrap_or_default()
+        );
+    }
+    for error in &sync_result.failed_files {
+        println!("{}", get_error_message(&error.to_string()));
+    }
+
+    if sync_result.dry_run {
+        println!(
+            "Dry run: {} out of {} custom XML files would be updated, {} created and {} deleted, nothing was written",
+            sync_result.synced_files.len(),
+            sync_result.total_count(),
+            sync_result.created_files.len(),
+            sync_result.deleted_files.len()
+        );
+        return;
+    }
+
+    println!(
+        "Successfully synced {} out of {} custom XML files ({} created, {} deleted, {} skipped as unchanged)",
+        sync_result.synced_files.len()
+            + sync_result.created_files.len()
+            + sync_result.deleted_files.len(),
+        sync_result.total_count(),
+        sync_result.created_files.len(),
+        sync_result.deleted_files.len(),
+        sync_result.skipped_files.len()
+    );
+}
@@ -0,0 +1,55 @@
+use colored::Colorize;
+use zip_utils::{Error, ValidationReport, validate_archive};
+
+use crate::cli::{
+    ensure_ooxml_exist::ensure_ooxml_exist,
+    input_utils::get_path_from_input::get_file_path_from_input,
+    print_utils::{exit_with_error, print_fn_progress},
+    types::UserPreference,
+};
+
+/// Validate the content types and relationships of the Word file
+pub fn validate_wrapper(user_preference: &mut UserPreference) {
+    println!("\n");
+    let fn_name = "Validate";
+    print_fn_progress(fn_name, "Validating...");
+
+    let package = get_file_path_from_input(user_preference);
+    package.file_path_info.print_info();
+
+    let validate_result = match ensure_ooxml_exist(&package, false) {
+        Ok(true) => package.validate(),
+        Ok(false) => package
+            .load()
+            .and_then(|package| validate_archive(&package)),
+        Err(e) => Err(e),
+    }
+    .and_then(print_validation_report);
+    if let Err(e) = validate_result {
+        exit_with_error(&e);
+    }
+
+    print_fn_progress(fn_name, "Validation finished!".green().to_string().as_str());
+}
+
+/// Print the package type of a valid package, an invalid one is turned into an error listing its issues
+pub fn print_validation_report(report: ValidationReport) -> Result<(), Error> {
+    if !report.is_valid() {
+        return Err(Error::Validation {
+            path: report.path,
+            issues: report.issues,
+        });
+    }
+
+    println!(
+        "{}",
+        format!(
+            "No issues found in {} ({:?} package)",
+            report.path.display(),
+            report.package_type
+        )
+        .green()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,71 @@
+//! Extract OOXML files (`.docx`, `.xlsx`, etc) into their folder representation, analyze them and pack them back
+//!
+//! ```no_run
+//! use zip_utils::{Package, RezipOptions, rezip_folder, summarize_to_file};
+//!
+//! let package = Package::open("sample_data/normal.docx")?;
+//! package.extract(true)?;
+//!
+//! let summary = summarize_to_file(&package)?;
+//! println!("{} files", summary.file_count);
+//!
+//! rezip_folder(
+//!     &package.extracted_folder,
+//!     "sample_data/normal_copy.docx",
+//!     &RezipOptions::for_extracted_folder(&package.extracted_folder)?,
+//! )?;
+//! # Ok::<(), zip_utils::Error>(())
+//! ```
+
+mod utils;
+
+pub use utils::{
+    analyze_custom_xml::item_props::{CustomXmlProps, parse_custom_xml_props},
+    analyze_custom_xml::main::{
+        CustomXmlAnalysis, CustomXmlElement, CustomXmlFile, CustomXmlFormat, CustomXmlInfo,
+        analyze_custom_xml, analyze_custom_xml_archive_to_file, analyze_custom_xml_in_archive,
+        analyze_custom_xml_to_file, parse_custom_xml_content, parse_custom_xml_content_for_tag,
+    },
+    content_types::{ContentTypeDefault, ContentTypeOverride, ContentTypes, PackageType},
+    data_bindings::main::{
+        DataBinding, DataBindingAnalysis, analyze_data_bindings, analyze_data_bindings_in_archive,
+    },
+    data_bindings::refresh::refresh_data_bindings,
+    data_bindings::xpath::{evaluate_xpath, parse_prefix_mappings},
+    error::Error,
+    files::{read_struct_from_json, write_struct_to_json},
+    ooxml_package::{OoxmlPackage, OoxmlPart},
+    package::Package,
+    relationships::{
+        Relationship, get_rels_part_name, get_source_part_name, parse_relationships,
+        resolve_relationship_target,
+    },
+    summarize::main::{
+        MediaInfo, SummarizeData, summarize, summarize_archive, summarize_archive_to_file,
+        summarize_to_file,
+    },
+    sync_custom_xml::create::create_custom_xml_part,
+    sync_custom_xml::delete::delete_custom_xml_part,
+    sync_custom_xml::diff::{JsonChange, SyncDiff, diff_custom_xml_info},
+    sync_custom_xml::main::{SyncOptions, SyncResult, sync_custom_xml},
+    sync_custom_xml::patch::patch_custom_xml_content,
+    sync_custom_xml::schema::{
+        CustomXmlSchemaValidator, CustomXmlSchemas, SchemaTarget, SchemaViolation,
+        read_custom_xml_schemas, register_custom_xml_schema,
+    },
+    types::{
+        CUSTOM_XML_FILE_NAME, CUSTOM_XML_SCHEMAS_FILE_NAME, EXTRACTED_FOLDER_NAME, FileInfo,
+        FilePathInfo, MANIFEST_FILE_NAME, SUMMARY_FILE_NAME, ZipFolder,
+    },
+    validate::{
+        main::{ValidationIssue, ValidationReport, validate_archive, validate_folder},
+        well_formed::{check_xml_well_formed, is_xml_part},
+    },
+    xml::{XmlAttributeSpan, XmlElement, XmlNode, XmlSpan},
+    zip_utils::main::{
+        CONTENT_TYPES_PART_NAME, Compression, PACKAGE_RELS_PART_NAME, RezipOptions, extract_zip,
+        extract_zip_from_reader, rezip_folder, rezip_folder_to_writer,
+    },
+    zip_utils::manifest::{ArchiveManifest, ManifestEntry, find_manifest_for_extracted_folder},
+    zip_utils::reextract::{ReextractResult, reextract_zip},
+};
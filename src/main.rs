@@ -1,11 +1,24 @@
-mod utils;
+mod cli;
 
-use crate::utils::{
-    input_utils::get_fn_to_call::get_fn_to_call, print_utils::print_error_with_panic,
+use crate::cli::{
+    input_utils::get_fn_to_call::get_fn_to_call,
+    main::{Cli, run_command},
+    print_utils::{GENERIC_EXIT_CODE, exit_with_error, print_error_and_exit},
 };
+use clap::Parser;
 use colored::Colorize;
 
 fn main() {
+    let cli = Cli::parse();
+
+    // Subcommands skip the banner and the menu so the tool can be scripted
+    if let Some(command) = cli.command {
+        if let Err(e) = run_command(command) {
+            exit_with_error(&e);
+        }
+        return;
+    }
+
     // Beautiful welcome banner
     println!();
     let border = "╔═══════════════════════════════════════════════════════╗".bright_cyan();
@@ -24,6 +37,6 @@ fn main() {
 
     match get_fn_to_call() {
         Ok(_) => (),
-        Err(e) => print_error_with_panic(e),
+        Err(e) => print_error_and_exit(e, GENERIC_EXIT_CODE),
     }
 }
@@ -0,0 +1,136 @@
+use serde::{Deserialize, Serialize};
+
+use crate::utils::{
+    relationships::{get_rels_part_name, parse_relationships, resolve_relationship_target},
+    xml::XmlElement,
+};
+
+const CUSTOM_XML_PROPS_RELATIONSHIP_TYPE_SUFFIX: &str = "/customXmlProps";
+
+/// The datastore properties of a custom XML part, read from its `itemPropsN.xml`
+///
+/// Word renumbers the custom XML parts when it saves a document, the item ID is what identifies a part
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CustomXmlProps {
+    /// The datastore item ID, e.g. `{32D1BF7B-7F44-494E-A02A-0CAFF277A406}`
+    pub item_id: String,
+    /// The URIs of the schemas the part conforms to
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub schema_refs: Vec<String>,
+}
+
+/// Parse the content of an `itemPropsN.xml` part
+///
+/// ```xml
+/// <ds:datastoreItem ds:itemID="{32D1BF7B-7F44-494E-A02A-0CAFF277A406}" xmlns:ds="...">
+///     <ds:schemaRefs><ds:schemaRef ds:uri="http://example.com/schema"/></ds:schemaRefs>
+/// </ds:datastoreItem>
+/// ```
+///
+/// The namespace prefix is ignored, only the local names are matched
+pub fn parse_custom_xml_props(content: &str) -> Result<CustomXmlProps, String> {
+    let root = XmlElement::parse(content)?;
+    if root.local_name() != "datastoreItem" {
+        return Err(format!(
+            "Expected a datastoreItem element, found {}",
+            root.tag
+        ));
+    }
+
+    let item_id = root
+        .get_attribute("itemID")
+        .ok_or("The datastoreItem element doesn't have an itemID")?
+        .to_string();
+    let schema_refs = root
+        .get_child_elements("schemaRefs")
+        .flat_map(|schema_refs| schema_refs.get_child_elements("schemaRef"))
+        .filter_map(|schema_ref| schema_ref.get_attribute("uri"))
+        .map(str::to_string)
+        .collect();
+
+    Ok(CustomXmlProps {
+        item_id,
+        schema_refs,
+    })
+}
+
+/// The part name of the item props of a custom XML part, e.g. `customXml/itemProps1.xml` for `customXml/item1.xml`
+///
+/// It's the target of the item's `customXmlProps` relationship, `read_part` returns the content of a part if it exists.
+/// Word always names them `itemPropsN.xml`, so that's the fallback if the item doesn't have any relationship.
+pub fn get_custom_xml_props_part_name(
+    item_part_name: &str,
+    read_part: impl Fn(&str) -> Option<String>,
+) -> String {
+    let props_part_name = read_part(&get_rels_part_name(item_part_name)).and_then(|content| {
+        parse_relationships(&content)
+            .ok()?
+            .into_iter()
+            .find(|relationship| {
+                relationship
+                    .relationship_type
+                    .ends_with(CUSTOM_XML_PROPS_RELATIONSHIP_TYPE_SUFFIX)
+            })
+            .map(|relationship| resolve_relationship_target(item_part_name, &relationship.target))
+    });
+
+    props_part_name.unwrap_or_else(|| match item_part_name.rsplit_once('/') {
+        Some((folder, file_name)) => {
+            format!("{}/{}", folder, file_name.replacen("item", "itemProps", 1))
+        }
+        None => item_part_name.replacen("item", "itemProps", 1),
+    })
+}
+
+/// The item props of a custom XML part, `None` if it doesn't have any or they can't be parsed
+pub fn find_custom_xml_props(
+    item_part_name: &str,
+    read_part: impl Fn(&str) -> Option<String>,
+) -> Option<CustomXmlProps> {
+    let props_part_name = get_custom_xml_props_part_name(item_part_name, &read_part);
+    parse_custom_xml_props(&read_part(&props_part_name)?).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_custom_xml_props() {
+        let props = parse_custom_xml_props(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="no"?>
+<ds:datastoreItem ds:itemID="{32D1BF7B-7F44-494E-A02A-0CAFF277A406}" xmlns:ds="http://schemas.openxmlformats.org/officeDocument/2006/customXml">
+    <ds:schemaRefs>
+        <ds:schemaRef ds:uri="http://example.com/a"/>
+        <ds:schemaRef ds:uri="http://example.com/b"/>
+    </ds:schemaRefs>
+</ds:datastoreItem>"#,
+        )
+        .unwrap();
+
+        assert_eq!(props.item_id, "{32D1BF7B-7F44-494E-A02A-0CAFF277A406}");
+        assert_eq!(
+            props.schema_refs,
+            vec!["http://example.com/a", "http://example.com/b"]
+        );
+
+        assert!(parse_custom_xml_props("<ds:datastoreItem/>").is_err());
+        assert!(parse_custom_xml_props("<root itemID=\"{A}\"/>").is_err());
+    }
+
+    #[test]
+    fn test_get_custom_xml_props_part_name() {
+        let item_rels = r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/customXmlProps" Target="itemProps7.xml"/></Relationships>"#;
+
+        assert_eq!(
+            get_custom_xml_props_part_name("customXml/item1.xml", |part_name| {
+                (part_name == "customXml/_rels/item1.xml.rels").then(|| item_rels.to_string())
+            }),
+            "customXml/itemProps7.xml"
+        );
+        assert_eq!(
+            get_custom_xml_props_part_name("customXml/item2.xml", |_| None),
+            "customXml/itemProps2.xml"
+        );
+    }
+}
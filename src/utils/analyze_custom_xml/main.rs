@@ -1,160 +1,420 @@
-use colored::Colorize;
-use fancy_regex::Regex;
+use quick_xml::{
+    Reader,
+    events::{Event, attributes::Attributes},
+};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
-use std::{collections::HashMap, fs::read_to_string, path::Path};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs::{create_dir_all, read_to_string},
+    path::Path,
+};
 
 use crate::utils::{
-    ensure_ooxml_exist::ensure_ooxml_exist,
+    analyze_custom_xml::item_props::{CustomXmlProps, find_custom_xml_props},
+    error::Error,
     files::{get_file_size_in_kb_from_bytes, is_file_custom_xml, visit_dirs, write_struct_to_json},
-    input_utils::get_path_from_input::get_file_path_from_input,
-    print_utils::{get_error_message, print_error_with_panic, print_fn_progress},
-    types::{CUSTOM_XML_FILE_NAME, FileInfo, FilePathInfo, UserPreference},
+    ooxml_package::OoxmlPackage,
+    package::Package,
+    types::{FileInfo, FilePathInfo},
+    xml::{XmlElement, XmlNode, resolve_entity},
 };
 
-#[derive(Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct CustomXmlFile {
     pub file_info: FileInfo,
+    /// The datastore properties from the part's `itemPropsN.xml`, missing if it doesn't have any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub item_props: Option<CustomXmlProps>,
     pub custom_xml_info: CustomXmlInfo,
 }
 
 #[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct CustomXmlInfo {
     pub tag: String,
+    /// How `json_content` maps to the content of the root element, missing in files written before it existed
+    #[serde(default)]
+    pub format: CustomXmlFormat,
     pub attributes: Option<serde_json::Value>,
     pub json_content: serde_json::Value,
+    /// The elements below the root holding JSON, only for [`CustomXmlFormat::Xml`]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub elements: Vec<CustomXmlElement>,
 }
 
-/// Analyze the custom XMLs in the extracted folder
-/// Note that this will only support custom XMLs in this format
-/// <someTag attribute1="value1" attribute2="value2">
-///   { jsonKey1:"value1" jsonKey2:"value2" }
-/// </someTag>
-/// And the result will be a json object like { "someTag": { "attribute1": "value1", "attribute2": "value2" } }
-///
-/// This doesn't support nested tags or multiple tags in the same file.
-pub fn analyze_custom_xml_wrapper(user_preference: &mut UserPreference) {
-    println!("\n");
-    let fn_name = "Analyze customXML";
-    print_fn_progress(fn_name, "Analyzing customXML...");
-
-    let file_path_info = get_file_path_from_input(user_preference);
-    file_path_info.print_info();
-
-    let analyze_result = analyze_custom_xml(&file_path_info);
-    if let Err(e) = analyze_result {
-        print_error_with_panic(e);
+/// How the content of a custom XML root element is represented in [`CustomXmlInfo::json_content`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CustomXmlFormat {
+    /// The root element holds a JSON text, `json_content` is that JSON
+    #[default]
+    Json,
+    /// Any other XML, `json_content` is the list of child nodes, see [`XmlNode`] for their JSON representation
+    ///
+    /// Elements whose text is a JSON object or array are listed in [`CustomXmlInfo::elements`] instead, their node in
+    /// `json_content` only keeps the tag
+    Xml,
+}
+
+/// An element below the root whose text is a JSON object or array
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CustomXmlElement {
+    /// The path of the element from the root, e.g. `/root/config`
+    ///
+    /// Siblings sharing a tag get their 1-based position, e.g. `/root/item[2]`
+    pub path: String,
+    pub attributes: Option<serde_json::Value>,
+    pub json_content: serde_json::Value,
+}
+
+impl CustomXmlInfo {
+    /// The info of a root element whose content is kept as XML nodes
+    pub fn from_xml_element(root: XmlElement) -> Self {
+        let mut children = root.children;
+        let mut elements = Vec::new();
+        take_json_elements(&mut children, &format!("/{}", root.tag), &mut elements);
+
+        CustomXmlInfo {
+            tag: root.tag,
+            format: CustomXmlFormat::Xml,
+            attributes: attributes_to_json(root.attributes),
+            json_content: json!(children),
+            elements,
+        }
     }
 
-    let (custom_xml_infos, root_folder) = analyze_result.unwrap();
+    /// The root element of the info, for [`CustomXmlFormat::Xml`] the JSON of [`CustomXmlInfo::elements`] is put back
+    ///
+    /// Errors if the attributes or the nodes are malformed, or if an element path doesn't match any element
+    pub fn to_xml_element(&self) -> Result<XmlElement, String> {
+        let attributes = attributes_from_json(&self.attributes)?;
+        if self.format == CustomXmlFormat::Json {
+            return Ok(XmlElement {
+                tag: self.tag.clone(),
+                attributes,
+                children: vec![XmlNode::Text(
+                    serde_json::to_string(&self.json_content).unwrap(),
+                )],
+            });
+        }
 
-    let output_path = format!("{}/{}", root_folder, CUSTOM_XML_FILE_NAME);
-    let write_result = write_struct_to_json(&custom_xml_infos, &output_path);
-    if write_result.is_err() {
-        print_error_with_panic(&format!(
-            "Failed to write the custom XML info to the file: {}",
-            write_result.err().unwrap()
-        ));
+        let mut children: Vec<XmlNode> = serde_json::from_value(self.json_content.clone())
+            .map_err(|e| format!("Invalid XML nodes: {}", e))?;
+
+        let mut elements_by_path: HashMap<&str, &CustomXmlElement> = self
+            .elements
+            .iter()
+            .map(|element| (element.path.as_str(), element))
+            .collect();
+        put_json_elements(
+            &mut children,
+            &format!("/{}", self.tag),
+            &mut elements_by_path,
+        )?;
+        if let Some(path) = elements_by_path.keys().next() {
+            return Err(format!("No element found at path {}", path));
+        }
+
+        Ok(XmlElement {
+            tag: self.tag.clone(),
+            attributes,
+            children,
+        })
+    }
+}
+
+/// The path of each child element, `None` for the other nodes
+fn get_child_paths(children: &[XmlNode], parent_path: &str) -> Vec<Option<String>> {
+    let mut tag_counts: HashMap<&str, usize> = HashMap::new();
+    for child in children {
+        if let XmlNode::Element(element) = child {
+            *tag_counts.entry(&element.tag).or_default() += 1;
+        }
+    }
+
+    let mut tag_positions: HashMap<&str, usize> = HashMap::new();
+    children
+        .iter()
+        .map(|child| {
+            let XmlNode::Element(element) = child else {
+                return None;
+            };
+
+            let position = tag_positions.entry(&element.tag).or_default();
+            *position += 1;
+            if tag_counts[element.tag.as_str()] > 1 {
+                Some(format!("{}/{}[{}]", parent_path, element.tag, position))
+            } else {
+                Some(format!("{}/{}", parent_path, element.tag))
+            }
+        })
+        .collect()
+}
+
+/// Move the elements holding a JSON object or array out of the tree, only their tag is left behind
+fn take_json_elements(
+    children: &mut [XmlNode],
+    parent_path: &str,
+    elements: &mut Vec<CustomXmlElement>,
+) {
+    let paths = get_child_paths(children, parent_path);
+    for (child, path) in children.iter_mut().zip(paths) {
+        let (XmlNode::Element(element), Some(path)) = (child, path) else {
+            continue;
+        };
+
+        let json_content = match element.children.as_slice() {
+            [XmlNode::Text(text)] if text.trim_start().starts_with(['{', '[']) => {
+                serde_json::from_str(text).ok()
+            }
+            _ => None,
+        };
+        match json_content {
+            Some(json_content) => {
+                elements.push(CustomXmlElement {
+                    path,
+                    attributes: attributes_to_json(std::mem::take(&mut element.attributes)),
+                    json_content,
+                });
+                element.children.clear();
+            }
+            None => take_json_elements(&mut element.children, &path, elements),
+        }
+    }
+}
+
+/// The reverse of [`take_json_elements`], the elements that were put back are removed from the map
+fn put_json_elements(
+    children: &mut [XmlNode],
+    parent_path: &str,
+    elements_by_path: &mut HashMap<&str, &CustomXmlElement>,
+) -> Result<(), String> {
+    let paths = get_child_paths(children, parent_path);
+    for (child, path) in children.iter_mut().zip(paths) {
+        let (XmlNode::Element(element), Some(path)) = (child, path) else {
+            continue;
+        };
+
+        match elements_by_path.remove(path.as_str()) {
+            Some(json_element) => {
+                element.attributes = attributes_from_json(&json_element.attributes)?;
+                element.children = vec![XmlNode::Text(
+                    serde_json::to_string(&json_element.json_content).unwrap(),
+                )];
+            }
+            None => put_json_elements(&mut element.children, &path, elements_by_path)?,
+        }
+    }
+
+    Ok(())
+}
+
+fn attributes_to_json(attributes: BTreeMap<String, String>) -> Option<serde_json::Value> {
+    if attributes.is_empty() {
+        None
+    } else {
+        Some(json!(attributes))
+    }
+}
+
+fn attributes_from_json(
+    attributes: &Option<serde_json::Value>,
+) -> Result<BTreeMap<String, String>, String> {
+    let mut attributes_map = BTreeMap::new();
+    if let Some(attrs) = attributes {
+        let attrs_obj = attrs
+            .as_object()
+            .ok_or("The attributes must be a JSON object")?;
+        for (key, value) in attrs_obj {
+            let value = value
+                .as_str()
+                .ok_or_else(|| format!("The value of the attribute {} must be a string", key))?;
+            attributes_map.insert(key.clone(), value.to_string());
+        }
+    }
+
+    Ok(attributes_map)
+}
+
+/// The result of analyzing the custom XMLs in an extracted folder
+#[derive(Debug, Default)]
+pub struct CustomXmlAnalysis {
+    /// The custom XML files that were parsed successfully
+    pub custom_xml_files: Vec<CustomXmlFile>,
+    /// The custom XML files that couldn't be read or parsed
+    pub skipped_files: Vec<Error>,
+}
+
+/// Analyze the custom XMLs of the package and write the result to the custom XML file inside the root folder
+///
+/// The custom XML file path is [`Package::custom_xml_file_path`]
+pub fn analyze_custom_xml_to_file(package: &Package) -> Result<CustomXmlAnalysis, Error> {
+    let analysis = package.analyze_custom_xml()?;
+    write_struct_to_json(&analysis.custom_xml_files, &package.custom_xml_file_path())?;
+
+    Ok(analysis)
+}
+
+/// Analyze the custom XMLs of the package straight from the archive, without extracting it, and write the result to the custom XML file
+///
+/// The root folder is created if needed, the custom XML file path is [`Package::custom_xml_file_path`]
+pub fn analyze_custom_xml_archive_to_file(package: &Package) -> Result<CustomXmlAnalysis, Error> {
+    let analysis = analyze_custom_xml_in_archive(&package.load()?);
+
+    create_dir_all(&package.root_folder).map_err(|e| Error::io(&package.root_folder, e))?;
+    write_struct_to_json(&analysis.custom_xml_files, &package.custom_xml_file_path())?;
+
+    Ok(analysis)
+}
+
+/// Analyze the custom XMLs straight from the in-memory package
+///
+/// The `full_file_path` of each custom XML is its part name inside the archive
+pub fn analyze_custom_xml_in_archive(ooxml_package: &OoxmlPackage) -> CustomXmlAnalysis {
+    let mut analysis = CustomXmlAnalysis::default();
+
+    for part in ooxml_package.parts() {
+        let file_name = part.name.rsplit('/').next().unwrap_or(&part.name);
+        if !is_file_custom_xml(file_name) {
+            continue;
+        }
+
+        let content = match ooxml_package.read_part_to_string(&part.name) {
+            Ok(content) => content,
+            Err(e) => {
+                analysis.skipped_files.push(e);
+                continue;
+            }
+        };
+
+        let file_info = FileInfo {
+            file_name_with_extension: file_name.to_string(),
+            full_file_path: part.name.clone(),
+            file_size_in_kb: get_file_size_in_kb_from_bytes(part.data.len() as u64),
+        };
+        let item_props = find_custom_xml_props(&part.name, |part_name| {
+            ooxml_package.read_part_to_string(part_name).ok()
+        });
+        match parse_custom_xml_file(file_info, item_props, &content) {
+            Ok(custom_xml_file) => analysis.custom_xml_files.push(custom_xml_file),
+            Err(e) => analysis.skipped_files.push(e),
+        }
     }
 
-    println!("Custom XML info file written at path: {}", output_path);
-    print_fn_progress(
-        fn_name,
-        "Analyzing customXML completed successfully!"
-            .green()
-            .to_string()
-            .as_str(),
-    );
+    analysis
 }
 
-fn analyze_custom_xml(
-    file_path_info: &FilePathInfo,
-) -> Result<(Vec<CustomXmlFile>, String), &'static str> {
-    let (extracted_folder, root_folder) = ensure_ooxml_exist(file_path_info)?;
+/// Parse the content of a single custom XML file
+fn parse_custom_xml_file(
+    file_info: FileInfo,
+    item_props: Option<CustomXmlProps>,
+    content: &str,
+) -> Result<CustomXmlFile, Error> {
+    /*
+        The content will usually be in the format of
+        <someTag attribute1="value1" attribute2="value2">
+            { jsonKey1:"value1" jsonKey2:"value2" }
+        </someTag>
+        Anything else is kept as XML nodes
+    */
+    match parse_custom_xml_content(content) {
+        Ok(custom_xml_info) => Ok(CustomXmlFile {
+            file_info,
+            item_props,
+            custom_xml_info,
+        }),
+        Err(e) => Err(Error::custom_xml_parse(&file_info.full_file_path, e)),
+    }
+}
 
-    let mut custom_xml_files: Vec<CustomXmlFile> = Vec::new();
+/// Analyze the custom XMLs in the extracted folder
+/// Custom XMLs in this format have their JSON parsed
+/// <someTag attribute1="value1" attribute2="value2">
+///   { jsonKey1:"value1" jsonKey2:"value2" }
+/// </someTag>
+/// And the result will be a json object like { "someTag": { "attribute1": "value1", "attribute2": "value2" } }
+///
+/// Any other custom XML is kept as XML nodes, see [`parse_custom_xml_content`]. Only malformed files are skipped.
+pub fn analyze_custom_xml(extracted_folder: &str) -> Result<CustomXmlAnalysis, Error> {
+    let output_path = Path::new(extracted_folder);
+    if !output_path.is_dir() {
+        return Err(Error::invalid_package(
+            output_path,
+            "The extracted folder does not exist",
+        ));
+    }
 
-    let mut parsed_file_count = 0;
-    let mut total_file_count = 0;
+    let mut analysis = CustomXmlAnalysis::default();
 
     // Visit the extracted folder and read the custom XML files
-    let output_path = Path::new(&extracted_folder);
     let visit_result = visit_dirs(output_path, &mut |entry| {
         let path = entry.path();
         let file_name = path.file_name().unwrap().to_string_lossy().to_string();
 
+        if !is_file_custom_xml(&file_name) {
+            return;
+        }
+
         let FilePathInfo {
             file_name_with_extension,
             file_size,
             full_file_path,
             ..
-        } = FilePathInfo::new(path.to_string_lossy().to_string());
-
-        if is_file_custom_xml(&file_name) {
-            total_file_count += 1;
-
-            // Read the content of the custom XML file as a string
-            let content = read_to_string(&path);
-            if content.is_err() {
-                let message = get_error_message(
-                    format!(
-                        "Failed to read the custom XML file: {}",
-                        path.to_string_lossy()
-                    )
-                    .as_str(),
-                );
-
-                println!("{}", message)
+        } = match FilePathInfo::new(path.to_string_lossy().to_string()) {
+            Ok(file_path_info) => file_path_info,
+            Err(e) => {
+                analysis.skipped_files.push(e);
+                return;
             }
+        };
 
-            /*
-                The content will be in the format of
-                <someTag attribute1="value1" attribute2="value2">
-                    { jsonKey1:"value1" jsonKey2:"value2" }
-                </someTag>
-            */
-            let content = content.unwrap();
-            match parse_custom_xml_content_for_tag(&content) {
-                Ok(custom_xml_info) => {
-                    custom_xml_files.push(CustomXmlFile {
-                        file_info: FileInfo {
-                            file_name_with_extension: file_name_with_extension.clone(),
-                            full_file_path: full_file_path.clone(),
-                            file_size_in_kb: get_file_size_in_kb_from_bytes(file_size),
-                        },
-                        custom_xml_info,
-                    });
-
-                    parsed_file_count += 1;
-                    println!(
-                        "{}",
-                        format!("Parsed custom XML content for file: {}", file_name).green()
-                    );
-                }
-                Err(_) => {
-                    println!(
-                        "{}",
-                        format!("Unsupported custom XML content for file: {}", file_name).yellow()
-                    );
-                }
+        // Read the content of the custom XML file as a string
+        let content = match read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) => {
+                analysis.skipped_files.push(Error::io(&path, e));
+                return;
             }
+        };
+
+        let file_info = FileInfo {
+            file_name_with_extension,
+            full_file_path,
+            file_size_in_kb: get_file_size_in_kb_from_bytes(file_size),
+        };
+        // The item props are found through the part names, i.e. the paths relative to the extracted folder
+        let item_props = path
+            .strip_prefix(output_path)
+            .ok()
+            .and_then(|relative_path| {
+                let item_part_name = relative_path.to_string_lossy().replace('\\', "/");
+                find_custom_xml_props(&item_part_name, |part_name| {
+                    read_to_string(output_path.join(part_name)).ok()
+                })
+            });
+        match parse_custom_xml_file(file_info, item_props, &content) {
+            Ok(custom_xml_file) => analysis.custom_xml_files.push(custom_xml_file),
+            Err(e) => analysis.skipped_files.push(e),
         }
     });
 
-    if visit_result.is_err() {
-        return Err("Failed to visit the directory");
-    } else {
-        println!(
-            "\n{}\n",
-            format!(
-                "Parsed {} custom XML files out of {} total files",
-                parsed_file_count, total_file_count
-            )
-            .green()
-        );
+    if let Err(e) = visit_result {
+        return Err(Error::io(output_path, e));
+    }
+
+    Ok(analysis)
+}
+
+/// Parse the custom XML content, the root element holding a JSON object or array is [`CustomXmlFormat::Json`] and
+/// anything else is [`CustomXmlFormat::Xml`]
+///
+/// Only malformed XML is an error
+pub fn parse_custom_xml_content(xml_content: &str) -> Result<CustomXmlInfo, String> {
+    if let Ok(custom_xml_info) = parse_custom_xml_content_for_tag(xml_content) {
+        return Ok(custom_xml_info);
     }
 
-    Ok((custom_xml_files, root_folder))
+    XmlElement::parse(xml_content).map(CustomXmlInfo::from_xml_element)
 }
 
 /// Parse the custom XML content for a tag
@@ -163,47 +423,89 @@ fn analyze_custom_xml(
 ///   { jsonKey1:"value1" jsonKey2:"value2" }
 /// </someTag>
 /// And the result will be a json object like { "someTag": { "attribute1": "value1", "attribute2": "value2" } }
-pub fn parse_custom_xml_content_for_tag(html_content: &str) -> Result<CustomXmlInfo, &'static str> {
-    let re = Regex::new(r#"(?s)<(\w+)([^>]*)>(.*?)</\1>"#).unwrap();
-
-    if let Ok(Some(caps)) = re.captures(html_content) {
-        let tag = caps.get(1).unwrap().as_str();
-
-        let attributes_as_string = caps.get(2).unwrap().as_str();
-        let attributes = parse_attributes(attributes_as_string)?;
-
-        let json_content_as_string = caps.get(3).unwrap().as_str();
-
-        match serde_json::from_str(json_content_as_string) {
-            Ok(json_content) => {
-                return Ok(CustomXmlInfo {
-                    tag: tag.to_string(),
-                    attributes,
-                    json_content,
-                });
+///
+/// The content is read with an XML reader, so the XML declaration, comments, namespaced tags (`ns0:root`), single-quoted
+/// attributes, entities (`&quot;`) and CDATA sections are all supported. The JSON is unescaped before it's parsed.
+pub fn parse_custom_xml_content_for_tag(xml_content: &str) -> Result<CustomXmlInfo, &'static str> {
+    const NOT_FOUND: &str = "Failed to find the custom XML content";
+    const UNSUPPORTED: &str = "Unsupported custom XML content";
+
+    let mut reader = Reader::from_str(xml_content);
+
+    // * Find the root element, everything before it (declaration, comments, etc) is skipped
+    let (tag, attributes) = loop {
+        match reader.read_event() {
+            Ok(Event::Start(element)) => {
+                let tag = String::from_utf8_lossy(element.name().as_ref()).to_string();
+                let attributes_as_string = String::from_utf8_lossy(element.attributes_raw());
+                break (tag, parse_attributes(attributes_as_string.trim())?);
             }
-            Err(_) => return Err("Unsupported custom XML content"),
+            // An empty root element doesn't have any JSON
+            Ok(Event::Empty(_)) => return Err(UNSUPPORTED),
+            Ok(Event::Eof) | Err(_) => return Err(NOT_FOUND),
+            Ok(_) => continue,
         }
+    };
+
+    // * Collect the unescaped text of the root element
+    let mut json_content_as_string = String::new();
+    loop {
+        match reader.read_event() {
+            Ok(Event::Text(text)) => {
+                json_content_as_string.push_str(&text.decode().map_err(|_| UNSUPPORTED)?);
+            }
+            Ok(Event::CData(cdata)) => {
+                json_content_as_string.push_str(&cdata.decode().map_err(|_| UNSUPPORTED)?);
+            }
+            Ok(Event::GeneralRef(reference)) => {
+                json_content_as_string.push(resolve_entity(&reference).ok_or(UNSUPPORTED)?);
+            }
+            // The reader makes sure the end tag matches the root element
+            Ok(Event::End(_)) => break,
+            // Nested elements are not JSON
+            Ok(Event::Start(_)) | Ok(Event::Empty(_)) => return Err(UNSUPPORTED),
+            Ok(Event::Eof) | Err(_) => return Err(NOT_FOUND),
+            Ok(_) => continue,
+        }
+    }
+
+    // Scalars like `5` or `true` are plain text, they would be rewritten on sync (`1e2` → `100.0`)
+    if !json_content_as_string.trim_start().starts_with(['{', '[']) {
+        return Err(UNSUPPORTED);
     }
 
-    Err("Failed to find the custom XML content")
+    match serde_json::from_str(&json_content_as_string) {
+        Ok(json_content) => Ok(CustomXmlInfo {
+            tag,
+            format: CustomXmlFormat::Json,
+            attributes,
+            json_content,
+            elements: Vec::new(),
+        }),
+        Err(_) => Err(UNSUPPORTED),
+    }
 }
 
 /// Parse the attribute string into a serde_json::Value
-/// An example of an attribute string is "attribute1=\"value1\" attribute2=\"value2\""
+/// An example of an attribute string is "attribute1=\"value1\" attribute2='value2'"
 /// And the result will be a json object like { "attribute1": "value1", "attribute2": "value2" }
+///
+/// Namespaced names such as `xmlns:ns0` are kept as is and the values are unescaped, malformed segments are ignored
 fn parse_attributes(attributes: &str) -> Result<Option<serde_json::Value>, &'static str> {
     if attributes.is_empty() {
         return Ok(None);
     }
 
-    let attr_re = Regex::new(r#"(\w+)="([^"]*)""#).unwrap();
-
-    // Collect attributes into a HashMap<String, String>
-    let mut attrs = HashMap::new();
-    for cap in attr_re.captures_iter(attributes) {
-        let cap_result = cap.unwrap();
-        attrs.insert(cap_result[1].to_string(), cap_result[2].to_string());
+    // Sorted by name like the attributes of the XML format, the JSON objects keep the insertion order
+    let mut attrs = BTreeMap::new();
+    for attribute in Attributes::new(attributes, 0).flatten() {
+        let Ok(value) = attribute.unescape_value() else {
+            continue;
+        };
+        attrs.insert(
+            String::from_utf8_lossy(attribute.key.as_ref()).to_string(),
+            value.to_string(),
+        );
     }
 
     Ok(Some(json!(attrs)))
@@ -257,11 +559,13 @@ mod tests {
 
         let expected_result = CustomXmlInfo {
             tag: "someTag".to_string(),
+            format: CustomXmlFormat::Json,
             attributes: Some(json!({
                 "attribute1": "value1",
                 "attribute2": "value2"
             })),
             json_content: json!({ "jsonKey1": "value1", "jsonKey2": "value2" }),
+            elements: Vec::new(),
         };
 
         assert!(result.is_ok());
@@ -275,10 +579,197 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_analyze_custom_xml_in_archive() {
+        use crate::utils::ooxml_package::build_test_package;
+
+        let ooxml_package = build_test_package(
+            "test.docx",
+            &[
+                (
+                    "customXml/item1.xml",
+                    r#"<someTag id="1">{"key":"value"}</someTag>"#,
+                ),
+                (
+                    "customXml/itemProps1.xml",
+                    r#"<ds:datastoreItem ds:itemID="{A}" xmlns:ds="urn:ds"/>"#,
+                ),
+                (
+                    "customXml/item2.xml",
+                    "<someTag><moreTag></moreTag></someTag>",
+                ),
+                ("customXml/item3.xml", "<someTag><moreTag></someTag>"),
+            ],
+        );
+
+        let analysis = analyze_custom_xml_in_archive(&ooxml_package);
+
+        assert_eq!(analysis.custom_xml_files.len(), 2);
+        let custom_xml_file = &analysis.custom_xml_files[0];
+        assert_eq!(
+            custom_xml_file.file_info.file_name_with_extension,
+            "item1.xml"
+        );
+        assert_eq!(
+            custom_xml_file.file_info.full_file_path,
+            "customXml/item1.xml"
+        );
+        assert_eq!(
+            custom_xml_file.custom_xml_info.json_content,
+            json!({ "key": "value" })
+        );
+        assert_eq!(
+            custom_xml_file.item_props,
+            Some(CustomXmlProps {
+                item_id: "{A}".to_string(),
+                schema_refs: Vec::new(),
+            })
+        );
+
+        // item2.xml doesn't contain JSON so it's kept as XML nodes
+        assert!(analysis.custom_xml_files[1].item_props.is_none());
+        let custom_xml_info = &analysis.custom_xml_files[1].custom_xml_info;
+        assert_eq!(custom_xml_info.format, CustomXmlFormat::Xml);
+        assert_eq!(custom_xml_info.json_content, json!([{ "tag": "moreTag" }]));
+
+        // item3.xml is malformed and itemProps1.xml isn't a custom XML file
+        assert_eq!(analysis.skipped_files.len(), 1);
+        assert!(
+            analysis.skipped_files[0]
+                .path()
+                .ends_with("customXml/item3.xml")
+        );
+    }
+
+    #[test]
+    fn test_parse_custom_xml_content_for_tag_office_generated() {
+        let content = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"no\"?>\n<!-- generated -->\n<ns0:root xmlns:ns0='http://example.com/ns' title=\"a &amp; b\">{&quot;key&quot;:&quot;&lt;value&gt;&quot;,<![CDATA[\"cdata\":\"x < y\"]]>}</ns0:root>";
+        let result = parse_custom_xml_content_for_tag(content).expect("content should parse");
+
+        assert_eq!(result.tag, "ns0:root");
+        assert_eq!(
+            result.attributes,
+            Some(json!({
+                "xmlns:ns0": "http://example.com/ns",
+                "title": "a & b"
+            }))
+        );
+        assert_eq!(
+            result.json_content,
+            json!({ "key": "<value>", "cdata": "x < y" })
+        );
+    }
+
     #[test]
     fn test_parse_custom_xml_content_for_tag_err_on_unsupported_content() {
         let content = r#"<someTag><moreTag></moreTag></someTag>"#; // invalid JSON
         let result = parse_custom_xml_content_for_tag(content);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_parse_custom_xml_content_falls_back_to_xml() {
+        let content = r#"<?xml version="1.0"?>
+<ns0:book xmlns:ns0="http://example.com/book" id="1">
+    <ns0:title>Rust &amp; XML</ns0:title>
+    <ns0:author/>
+</ns0:book>"#;
+
+        let custom_xml_info = parse_custom_xml_content(content).unwrap();
+
+        assert_eq!(custom_xml_info.tag, "ns0:book");
+        assert_eq!(custom_xml_info.format, CustomXmlFormat::Xml);
+        assert_eq!(
+            custom_xml_info.attributes,
+            Some(json!({ "xmlns:ns0": "http://example.com/book", "id": "1" }))
+        );
+        assert_eq!(
+            custom_xml_info.json_content,
+            json!([
+                { "tag": "ns0:title", "children": ["Rust & XML"] },
+                { "tag": "ns0:author" }
+            ])
+        );
+
+        // The XML written back parses to the same info
+        let xml = custom_xml_info.to_xml_element().unwrap().to_xml_string();
+        assert_eq!(parse_custom_xml_content(&xml).unwrap(), custom_xml_info);
+
+        // JSON content still wins
+        let custom_xml_info = parse_custom_xml_content(r#"<a>{"key":1}</a>"#).unwrap();
+        assert_eq!(custom_xml_info.format, CustomXmlFormat::Json);
+
+        // Only an object or an array is JSON, a scalar is kept as text
+        for content in ["<count>5</count>", "<flag>true</flag>", "<n>1e2</n>"] {
+            let custom_xml_info = parse_custom_xml_content(content).unwrap();
+            assert_eq!(custom_xml_info.format, CustomXmlFormat::Xml);
+            let xml = custom_xml_info.to_xml_element().unwrap().to_xml_string();
+            assert_eq!(xml, content);
+        }
+    }
+
+    #[test]
+    fn test_parse_custom_xml_content_json_elements() {
+        let content = r#"<root version="2">
+    <config env="prod">{"debug":false}</config>
+    <items>
+        <item>[1,2]</item>
+        <item>not json</item>
+        <item>{"id":3}</item>
+    </items>
+</root>"#;
+
+        let custom_xml_info = parse_custom_xml_content(content).unwrap();
+
+        assert_eq!(custom_xml_info.format, CustomXmlFormat::Xml);
+        assert_eq!(
+            custom_xml_info.json_content,
+            json!([
+                { "tag": "config" },
+                {
+                    "tag": "items",
+                    "children": [
+                        { "tag": "item" },
+                        { "tag": "item", "children": ["not json"] },
+                        { "tag": "item" }
+                    ]
+                }
+            ])
+        );
+        assert_eq!(
+            custom_xml_info.elements,
+            vec![
+                CustomXmlElement {
+                    path: "/root/config".to_string(),
+                    attributes: Some(json!({ "env": "prod" })),
+                    json_content: json!({ "debug": false }),
+                },
+                CustomXmlElement {
+                    path: "/root/items/item[1]".to_string(),
+                    attributes: None,
+                    json_content: json!([1, 2]),
+                },
+                CustomXmlElement {
+                    path: "/root/items/item[3]".to_string(),
+                    attributes: None,
+                    json_content: json!({ "id": 3 }),
+                },
+            ]
+        );
+
+        let xml = custom_xml_info.to_xml_element().unwrap().to_xml_string();
+        assert_eq!(
+            xml,
+            r#"<root version="2"><config env="prod">{"debug":false}</config><items><item>[1,2]</item><item>not json</item><item>{"id":3}</item></items></root>"#
+        );
+        assert_eq!(parse_custom_xml_content(&xml).unwrap(), custom_xml_info);
+    }
+
+    #[test]
+    fn test_to_xml_element_unknown_path() {
+        let mut custom_xml_info = parse_custom_xml_content("<root><a>{}</a></root>").unwrap();
+        custom_xml_info.elements[0].path = "/root/b".to_string();
+
+        assert!(custom_xml_info.to_xml_element().is_err());
+    }
 }
@@ -1 +1,2 @@
+pub mod item_props;
 pub mod main;
@@ -0,0 +1,340 @@
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path};
+
+use crate::utils::{
+    error::Error, ooxml_package::OoxmlPackage, xml::XmlElement,
+    zip_utils::main::CONTENT_TYPES_PART_NAME,
+};
+
+/// The kind of application a package belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PackageType {
+    Word,
+    Excel,
+    PowerPoint,
+    /// Any other Open Packaging Conventions package
+    Opc,
+}
+
+impl PackageType {
+    /// The folder that holds the main part and the other application specific parts, `None` for a generic package
+    pub fn main_folder(&self) -> Option<&'static str> {
+        match self {
+            Self::Word => Some("word"),
+            Self::Excel => Some("xl"),
+            Self::PowerPoint => Some("ppt"),
+            Self::Opc => None,
+        }
+    }
+}
+
+/// A content type of the main part, with the package type and the file extension it belongs to
+struct MainContentType {
+    content_type: &'static str,
+    package_type: PackageType,
+    extension: &'static str,
+}
+
+const MAIN_CONTENT_TYPES: [MainContentType; 15] = [
+    MainContentType {
+        content_type: "application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml",
+        package_type: PackageType::Word,
+        extension: "docx",
+    },
+    MainContentType {
+        content_type: "application/vnd.openxmlformats-officedocument.wordprocessingml.template.main+xml",
+        package_type: PackageType::Word,
+        extension: "dotx",
+    },
+    MainContentType {
+        content_type: "application/vnd.ms-word.document.macroEnabled.main+xml",
+        package_type: PackageType::Word,
+        extension: "docm",
+    },
+    MainContentType {
+        content_type: "application/vnd.ms-word.template.macroEnabledTemplate.main+xml",
+        package_type: PackageType::Word,
+        extension: "dotm",
+    },
+    MainContentType {
+        content_type: "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml",
+        package_type: PackageType::Excel,
+        extension: "xlsx",
+    },
+    MainContentType {
+        content_type: "application/vnd.openxmlformats-officedocument.spreadsheetml.template.main+xml",
+        package_type: PackageType::Excel,
+        extension: "xltx",
+    },
+    MainContentType {
+        content_type: "application/vnd.ms-excel.sheet.macroEnabled.main+xml",
+        package_type: PackageType::Excel,
+        extension: "xlsm",
+    },
+    MainContentType {
+        content_type: "application/vnd.ms-excel.template.macroEnabled.main+xml",
+        package_type: PackageType::Excel,
+        extension: "xltm",
+    },
+    MainContentType {
+        content_type: "application/vnd.openxmlformats-officedocument.presentationml.presentation.main+xml",
+        package_type: PackageType::PowerPoint,
+        extension: "pptx",
+    },
+    MainContentType {
+        content_type: "application/vnd.openxmlformats-officedocument.presentationml.slideshow.main+xml",
+        package_type: PackageType::PowerPoint,
+        extension: "ppsx",
+    },
+    MainContentType {
+        content_type: "application/vnd.openxmlformats-officedocument.presentationml.template.main+xml",
+        package_type: PackageType::PowerPoint,
+        extension: "potx",
+    },
+    MainContentType {
+        content_type: "application/vnd.ms-powerpoint.presentation.macroEnabled.main+xml",
+        package_type: PackageType::PowerPoint,
+        extension: "pptm",
+    },
+    MainContentType {
+        content_type: "application/vnd.ms-powerpoint.slideshow.macroEnabled.main+xml",
+        package_type: PackageType::PowerPoint,
+        extension: "ppsm",
+    },
+    MainContentType {
+        content_type: "application/vnd.ms-powerpoint.template.macroEnabled.main+xml",
+        package_type: PackageType::PowerPoint,
+        extension: "potm",
+    },
+    MainContentType {
+        content_type: "application/vnd.ms-powerpoint.addin.macroEnabled.main+xml",
+        package_type: PackageType::PowerPoint,
+        extension: "ppam",
+    },
+];
+
+/// A `<Default>` element, the content type of every part with the given extension
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContentTypeDefault {
+    pub extension: String,
+    pub content_type: String,
+}
+
+/// An `<Override>` element, the content type of a single part
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContentTypeOverride {
+    /// Name of the part with a leading `/`, e.g. `/word/document.xml`
+    pub part_name: String,
+    pub content_type: String,
+}
+
+/// The content of `[Content_Types].xml`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContentTypes {
+    pub defaults: Vec<ContentTypeDefault>,
+    pub overrides: Vec<ContentTypeOverride>,
+}
+
+impl ContentTypes {
+    /// Parse the content of `[Content_Types].xml`, elements missing an attribute are ignored
+    ///
+    /// The attribute values are unescaped, and commented-out elements are skipped like any other comment
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let root = XmlElement::parse(content)?;
+
+        let mut content_types = Self::default();
+        for element in root.get_child_elements("Default") {
+            if let (Some(extension), Some(content_type)) = (
+                element.get_attribute("Extension"),
+                element.get_attribute("ContentType"),
+            ) {
+                content_types.defaults.push(ContentTypeDefault {
+                    extension: extension.to_string(),
+                    content_type: content_type.to_string(),
+                });
+            }
+        }
+        for element in root.get_child_elements("Override") {
+            if let (Some(part_name), Some(content_type)) = (
+                element.get_attribute("PartName"),
+                element.get_attribute("ContentType"),
+            ) {
+                content_types.overrides.push(ContentTypeOverride {
+                    part_name: part_name.to_string(),
+                    content_type: content_type.to_string(),
+                });
+            }
+        }
+
+        Ok(content_types)
+    }
+
+    /// Read `[Content_Types].xml` from the extracted folder
+    pub fn read_from_folder(extracted_folder: impl AsRef<Path>) -> Result<Self, Error> {
+        let extracted_folder = extracted_folder.as_ref();
+        let file_path = extracted_folder.join(CONTENT_TYPES_PART_NAME);
+        if !file_path.is_file() {
+            return Err(Error::invalid_package(
+                extracted_folder,
+                format!("The package does not contain {}", CONTENT_TYPES_PART_NAME),
+            ));
+        }
+
+        let content = fs::read_to_string(&file_path).map_err(|e| Error::io(&file_path, e))?;
+        Self::parse(&content).map_err(|e| Error::invalid_package(&file_path, e))
+    }
+
+    /// Read `[Content_Types].xml` from the in-memory package
+    pub fn read_from_archive(ooxml_package: &OoxmlPackage) -> Result<Self, Error> {
+        if !ooxml_package.has_part(CONTENT_TYPES_PART_NAME) {
+            return Err(Error::invalid_package(
+                &ooxml_package.path,
+                format!("The package does not contain {}", CONTENT_TYPES_PART_NAME),
+            ));
+        }
+
+        let content = ooxml_package.read_part_to_string(CONTENT_TYPES_PART_NAME)?;
+        Self::parse(&content).map_err(|e| {
+            Error::invalid_package(ooxml_package.path.join(CONTENT_TYPES_PART_NAME), e)
+        })
+    }
+
+    /// The content type of a part, the override comes first and then the default for its extension
+    ///
+    /// The part name is the name inside the archive, with or without the leading `/`. Both lookups are case-insensitive.
+    pub fn get_content_type(&self, part_name: &str) -> Option<&str> {
+        let part_name = part_name.trim_start_matches('/');
+        let override_content_type = self.overrides.iter().find(|content_type| {
+            content_type
+                .part_name
+                .trim_start_matches('/')
+                .eq_ignore_ascii_case(part_name)
+        });
+        if let Some(content_type) = override_content_type {
+            return Some(&content_type.content_type);
+        }
+
+        // `Path::extension` doesn't work for `_rels/.rels`, the whole file name is its extension
+        let file_name = part_name.rsplit('/').next().unwrap_or(part_name);
+        let (_, extension) = file_name.rsplit_once('.')?;
+        self.defaults
+            .iter()
+            .find(|content_type| content_type.extension.eq_ignore_ascii_case(extension))
+            .map(|content_type| content_type.content_type.as_str())
+    }
+
+    /// The name of the main part inside the archive (without the leading `/`), `None` for a generic package
+    pub fn main_part_name(&self) -> Option<&str> {
+        self.find_main_part()
+            .map(|(content_type, _)| content_type.part_name.trim_start_matches('/'))
+    }
+
+    /// The kind of package, detected from the content type of the main part
+    pub fn package_type(&self) -> PackageType {
+        self.find_main_part()
+            .map(|(_, main_content_type)| main_content_type.package_type)
+            .unwrap_or(PackageType::Opc)
+    }
+
+    /// The file extension matching the main part, e.g. `docm` for a macro enabled Word document, `zip` for a generic package
+    pub fn extension(&self) -> &'static str {
+        self.find_main_part()
+            .map(|(_, main_content_type)| main_content_type.extension)
+            .unwrap_or("zip")
+    }
+
+    /// The override of the main part together with its known content type
+    fn find_main_part(&self) -> Option<(&ContentTypeOverride, &'static MainContentType)> {
+        self.overrides.iter().find_map(|content_type| {
+            MAIN_CONTENT_TYPES
+                .iter()
+                .find(|main_content_type| {
+                    main_content_type
+                        .content_type
+                        .eq_ignore_ascii_case(&content_type.content_type)
+                })
+                .map(|main_content_type| (content_type, main_content_type))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PRESENTATION_CONTENT_TYPES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">
+    <Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>
+    <Default Extension="xml" ContentType="application/xml"/>
+    <Default Extension="PNG" ContentType="image/png"/>
+    <Override PartName="/ppt/presentation.xml" ContentType="application/vnd.openxmlformats-officedocument.presentationml.presentation.main+xml"/>
+    <Override ContentType="application/vnd.openxmlformats-officedocument.presentationml.slide+xml" PartName="/ppt/slides/slide1.xml"/>
+</Types>"#;
+
+    #[test]
+    fn test_parse_content_types() {
+        let content_types = ContentTypes::parse(PRESENTATION_CONTENT_TYPES).unwrap();
+
+        assert_eq!(content_types.defaults.len(), 3);
+        assert_eq!(content_types.overrides.len(), 2);
+        // The attributes can come in any order
+        assert_eq!(
+            content_types.overrides[1].part_name,
+            "/ppt/slides/slide1.xml"
+        );
+
+        // Entities are unescaped and comments are not elements
+        let content_types = ContentTypes::parse(
+            r#"<Types><!-- <Override PartName="/old.xml" ContentType="application/xml"/> --><Override PartName="/a&amp;b.xml" ContentType="application/xml"/></Types>"#,
+        )
+        .unwrap();
+        assert_eq!(content_types.overrides.len(), 1);
+        assert_eq!(
+            content_types.get_content_type("a&b.xml"),
+            Some("application/xml")
+        );
+        assert!(ContentTypes::parse("<Types><Override>").is_err());
+    }
+
+    #[test]
+    fn test_detect_package_type() {
+        let content_types = ContentTypes::parse(PRESENTATION_CONTENT_TYPES).unwrap();
+        assert_eq!(content_types.package_type(), PackageType::PowerPoint);
+        assert_eq!(content_types.extension(), "pptx");
+        assert_eq!(content_types.main_part_name(), Some("ppt/presentation.xml"));
+
+        let macro_enabled = ContentTypes::parse(
+            r#"<Types><Override PartName="/word/document.xml" ContentType="application/vnd.ms-word.document.macroEnabled.main+xml"/></Types>"#,
+        )
+        .unwrap();
+        assert_eq!(macro_enabled.package_type(), PackageType::Word);
+        assert_eq!(macro_enabled.extension(), "docm");
+
+        let generic = ContentTypes::parse(
+            r#"<Types><Default Extension="xml" ContentType="application/xml"/></Types>"#,
+        )
+        .unwrap();
+        assert_eq!(generic.package_type(), PackageType::Opc);
+        assert_eq!(generic.extension(), "zip");
+        assert_eq!(generic.main_part_name(), None);
+    }
+
+    #[test]
+    fn test_get_content_type() {
+        let content_types = ContentTypes::parse(PRESENTATION_CONTENT_TYPES).unwrap();
+
+        assert_eq!(
+            content_types.get_content_type("ppt/slides/slide1.xml"),
+            Some("application/vnd.openxmlformats-officedocument.presentationml.slide+xml")
+        );
+        assert_eq!(
+            content_types.get_content_type("/ppt/media/image1.png"),
+            Some("image/png")
+        );
+        assert_eq!(
+            content_types.get_content_type("_rels/.rels"),
+            Some("application/vnd.openxmlformats-package.relationships+xml")
+        );
+        assert_eq!(content_types.get_content_type("ppt/media/video.mp4"), None);
+    }
+}
@@ -0,0 +1,392 @@
+use serde::{Deserialize, Serialize};
+use std::{collections::HashSet, fs, path::Path};
+
+use crate::utils::{
+    analyze_custom_xml::item_props::find_custom_xml_props,
+    content_types::ContentTypes,
+    data_bindings::xpath::{evaluate_xpath, parse_prefix_mappings},
+    error::Error,
+    files::{get_part_names_in_folder, is_file_custom_xml},
+    ooxml_package::OoxmlPackage,
+    relationships::{get_rels_part_name, parse_relationships, resolve_relationship_target},
+    xml::{XmlElement, XmlNode, XmlSpan},
+    zip_utils::main::CONTENT_TYPES_PART_NAME,
+};
+
+/// The relationship types from the main part to its headers and footers, the transitional and strict namespaces only
+/// differ in the prefix
+const HEADER_FOOTER_RELATIONSHIP_TYPE_SUFFIXES: [&str; 2] = ["/header", "/footer"];
+
+/// A content control (`w:sdt`) bound to a custom XML part through its `w:dataBinding`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DataBinding {
+    /// The part holding the content control, e.g. `word/document.xml` or `word/header1.xml`
+    pub part_name: String,
+    /// The `w:tag` of the content control
+    pub tag: Option<String>,
+    /// The `w:alias` of the content control, i.e. its title in Word
+    pub alias: Option<String>,
+    /// The datastore item ID of the bound custom XML part, any part can be bound if it's missing
+    pub store_item_id: Option<String>,
+    pub xpath: String,
+    /// The namespaces of the prefixes used in the XPath, e.g. `xmlns:ns0='http://example.com/data'`
+    pub prefix_mappings: String,
+    /// The custom XML part the XPath was resolved against, `None` if no part matches
+    pub custom_xml_part_name: Option<String>,
+    /// The text the content control shows, as last saved by Word
+    pub text: String,
+    /// The value the XPath resolves to in the custom XML data, `None` if it doesn't select anything
+    pub value: Option<String>,
+    /// Why the XPath couldn't be evaluated
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// The bound content controls of a Word document
+#[derive(Debug, Default)]
+pub struct DataBindingAnalysis {
+    pub data_bindings: Vec<DataBinding>,
+    /// The document parts that couldn't be read or parsed
+    pub skipped_parts: Vec<Error>,
+}
+
+/// A custom XML part a data binding can point to
+pub struct CustomXmlPart {
+    pub part_name: String,
+    /// The datastore item ID of its item props
+    pub item_id: Option<String>,
+    pub root: XmlElement,
+}
+
+/// List the bound content controls of the extracted folder, see [`analyze_data_bindings_in_archive`]
+pub fn analyze_data_bindings(extracted_folder: &str) -> Result<DataBindingAnalysis, Error> {
+    let folder_path = Path::new(extracted_folder);
+    if !folder_path.is_dir() {
+        return Err(Error::invalid_package(
+            folder_path,
+            "The extracted folder does not exist",
+        ));
+    }
+
+    let part_names = get_part_names_in_folder(folder_path)?;
+
+    analyze_data_bindings_in_parts(&part_names, &|part_name| {
+        let part_path = folder_path.join(part_name);
+        fs::read_to_string(&part_path).map_err(|e| Error::io(&part_path, e))
+    })
+}
+
+/// List the bound content controls of the package straight from the archive
+///
+/// The main document part, its headers and its footers are searched. The XPath of each binding is evaluated against
+/// the custom XML part whose item props have its `w:storeItemID`, see [`evaluate_xpath`]
+pub fn analyze_data_bindings_in_archive(
+    ooxml_package: &OoxmlPackage,
+) -> Result<DataBindingAnalysis, Error> {
+    let part_names: Vec<String> = ooxml_package.part_names().map(str::to_string).collect();
+
+    analyze_data_bindings_in_parts(&part_names, &|part_name| {
+        ooxml_package.read_part_to_string(part_name)
+    })
+}
+
+fn analyze_data_bindings_in_parts(
+    part_names: &[String],
+    read_part: &dyn Fn(&str) -> Result<String, Error>,
+) -> Result<DataBindingAnalysis, Error> {
+    let mut analysis = DataBindingAnalysis::default();
+    let custom_xml_parts = get_custom_xml_parts(part_names, read_part);
+
+    for part_name in get_document_part_names(read_part)? {
+        let (root, span) = match read_part(&part_name).and_then(|content| {
+            XmlElement::parse_with_spans(&content)
+                .map_err(|e| Error::custom_xml_parse(&part_name, e))
+        }) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                analysis.skipped_parts.push(e);
+                continue;
+            }
+        };
+
+        let mut content_controls = Vec::new();
+        find_bound_content_controls(&root, &span, &mut content_controls);
+        for (content_control, _) in content_controls {
+            analysis.data_bindings.push(get_data_binding(
+                &part_name,
+                content_control,
+                &custom_xml_parts,
+            ));
+        }
+    }
+
+    Ok(analysis)
+}
+
+/// The main document part followed by its headers and footers
+pub fn get_document_part_names(
+    read_part: &dyn Fn(&str) -> Result<String, Error>,
+) -> Result<Vec<String>, Error> {
+    let content_types = ContentTypes::parse(&read_part(CONTENT_TYPES_PART_NAME)?)
+        .map_err(|e| Error::invalid_package(CONTENT_TYPES_PART_NAME, e))?;
+    let Some(main_part_name) = content_types.main_part_name() else {
+        return Ok(Vec::new());
+    };
+
+    let mut part_names = vec![main_part_name.to_string()];
+    let main_rels_part_name = get_rels_part_name(main_part_name);
+    if let Ok(main_rels_content) = read_part(&main_rels_part_name) {
+        let relationships = parse_relationships(&main_rels_content)
+            .map_err(|e| Error::invalid_package(&main_rels_part_name, e))?;
+        let mut seen_part_names = HashSet::new();
+        for relationship in relationships {
+            let is_header_or_footer = HEADER_FOOTER_RELATIONSHIP_TYPE_SUFFIXES
+                .iter()
+                .any(|suffix| relationship.relationship_type.ends_with(suffix));
+            if is_header_or_footer && !relationship.is_external {
+                let part_name = resolve_relationship_target(main_part_name, &relationship.target);
+                if seen_part_names.insert(part_name.clone()) {
+                    part_names.push(part_name);
+                }
+            }
+        }
+    }
+
+    Ok(part_names)
+}
+
+/// The custom XML parts that can be parsed, with the datastore item ID of their item props
+pub fn get_custom_xml_parts(
+    part_names: &[String],
+    read_part: &dyn Fn(&str) -> Result<String, Error>,
+) -> Vec<CustomXmlPart> {
+    part_names
+        .iter()
+        .filter(|part_name| is_file_custom_xml(part_name.rsplit('/').next().unwrap_or(part_name)))
+        .filter_map(|part_name| {
+            let root = XmlElement::parse(&read_part(part_name).ok()?).ok()?;
+            let item_id =
+                find_custom_xml_props(part_name, |props_part_name| read_part(props_part_name).ok())
+                    .map(|item_props| item_props.item_id);
+            Some(CustomXmlPart {
+                part_name: part_name.clone(),
+                item_id,
+                root,
+            })
+        })
+        .collect()
+}
+
+/// The content controls (`w:sdt`) with a `w:dataBinding` and their spans, nested ones included, in document order
+pub fn find_bound_content_controls<'a>(
+    element: &'a XmlElement,
+    span: &'a XmlSpan,
+    content_controls: &mut Vec<(&'a XmlElement, &'a XmlSpan)>,
+) {
+    for (child, child_span) in element.children.iter().zip(&span.children) {
+        let XmlNode::Element(child) = child else {
+            continue;
+        };
+
+        if child.local_name() == "sdt" && get_data_binding_element(child).is_some() {
+            content_controls.push((child, child_span));
+        }
+        find_bound_content_controls(child, child_span, content_controls);
+    }
+}
+
+fn get_data_binding_element(content_control: &XmlElement) -> Option<&XmlElement> {
+    content_control
+        .get_child_elements("sdtPr")
+        .next()?
+        .get_child_elements("dataBinding")
+        .next()
+}
+
+/// The data binding of a content control, resolved against the custom XML parts
+pub fn get_data_binding(
+    part_name: &str,
+    content_control: &XmlElement,
+    custom_xml_parts: &[CustomXmlPart],
+) -> DataBinding {
+    let properties = content_control.get_child_elements("sdtPr").next();
+    let get_property = |local_name: &str| {
+        properties
+            .and_then(|properties| properties.get_child_elements(local_name).next())
+            .and_then(|property| property.get_attribute("val"))
+            .map(str::to_string)
+    };
+    let data_binding = get_data_binding_element(content_control);
+    let get_binding_attribute = |local_name: &str| {
+        data_binding
+            .and_then(|data_binding| data_binding.get_attribute(local_name))
+            .map(str::to_string)
+    };
+
+    // The text runs of the content, nested content controls included
+    let text = content_control
+        .get_child_elements("sdtContent")
+        .map(get_run_text)
+        .collect();
+
+    let mut data_binding = DataBinding {
+        part_name: part_name.to_string(),
+        tag: get_property("tag"),
+        alias: get_property("alias"),
+        store_item_id: get_binding_attribute("storeItemID"),
+        xpath: get_binding_attribute("xpath").unwrap_or_default(),
+        prefix_mappings: get_binding_attribute("prefixMappings").unwrap_or_default(),
+        custom_xml_part_name: None,
+        text,
+        value: None,
+        error: None,
+    };
+    resolve_data_binding(&mut data_binding, custom_xml_parts);
+
+    data_binding
+}
+
+/// Find the bound custom XML part and evaluate the XPath against it
+///
+/// Without a store item ID the first part the XPath selects something in is used, like Word does
+fn resolve_data_binding(data_binding: &mut DataBinding, custom_xml_parts: &[CustomXmlPart]) {
+    let prefix_mappings = parse_prefix_mappings(&data_binding.prefix_mappings);
+    let candidate_parts: Vec<&CustomXmlPart> = match &data_binding.store_item_id {
+        Some(store_item_id) => custom_xml_parts
+            .iter()
+            .filter(|custom_xml_part| {
+                custom_xml_part
+                    .item_id
+                    .as_ref()
+                    .is_some_and(|item_id| item_id.eq_ignore_ascii_case(store_item_id))
+            })
+            .collect(),
+        None => custom_xml_parts.iter().collect(),
+    };
+
+    for custom_xml_part in &candidate_parts {
+        match evaluate_xpath(&custom_xml_part.root, &data_binding.xpath, &prefix_mappings) {
+            Ok(Some(value)) => {
+                data_binding.custom_xml_part_name = Some(custom_xml_part.part_name.clone());
+                data_binding.value = Some(value);
+                return;
+            }
+            Ok(None) => {}
+            Err(e) => {
+                data_binding.error = Some(e);
+                return;
+            }
+        }
+    }
+
+    // Nothing selected, the part found by its item ID is still the bound one
+    if data_binding.store_item_id.is_some() {
+        match candidate_parts.first() {
+            Some(custom_xml_part) => {
+                data_binding.custom_xml_part_name = Some(custom_xml_part.part_name.clone())
+            }
+            None => {
+                data_binding.error = Some("No custom XML part has this store item ID".to_string())
+            }
+        }
+    }
+}
+
+/// The text of the `w:t` elements below the element, a `w:tab` is a tab and a `w:br` or `w:cr` a line break
+pub fn get_run_text(element: &XmlElement) -> String {
+    let mut text = String::new();
+    for child in &element.children {
+        let XmlNode::Element(child) = child else {
+            continue;
+        };
+
+        match child.local_name() {
+            "t" => text.push_str(&child.get_text()),
+            "tab" => text.push('\t'),
+            "br" | "cr" => text.push('\n'),
+            _ => text.push_str(&get_run_text(child)),
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    const PREFIX_MAPPINGS: &str = "xmlns:ns0='urn:data'";
+
+    fn get_content_control(tag: &str, xpath: &str, store_item_id: &str, text: &str) -> String {
+        format!(
+            r#"<w:sdt><w:sdtPr><w:alias w:val="{tag} title"/><w:tag w:val="{tag}"/><w:dataBinding w:prefixMappings="{PREFIX_MAPPINGS}" w:xpath="{xpath}" w:storeItemID="{store_item_id}"/></w:sdtPr><w:sdtContent><w:r><w:t>{text}</w:t></w:r></w:sdtContent></w:sdt>"#
+        )
+    }
+
+    #[test]
+    fn test_analyze_data_bindings_in_parts() {
+        let document = format!(
+            r#"<w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main"><w:body><w:p>{}</w:p><w:sdt><w:sdtContent><w:p><w:r><w:t>Not bound</w:t></w:r></w:p></w:sdtContent></w:sdt>{}</w:body></w:document>"#,
+            get_content_control("name", "/ns0:root[1]/ns0:name[1]", "{A}", "Old name"),
+            get_content_control("missing", "/ns0:root[1]/ns0:name[1]", "{B}", ""),
+        );
+        let header = format!(
+            r#"<w:hdr xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">{}</w:hdr>"#,
+            get_content_control("version", "/ns0:root[1]/@ns0:version", "{a}", "1")
+        );
+        let parts: HashMap<&str, String> = HashMap::from([
+            (
+                CONTENT_TYPES_PART_NAME,
+                r#"<Types><Override PartName="/word/document.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml"/></Types>"#.to_string(),
+            ),
+            (
+                "word/_rels/document.xml.rels",
+                r#"<Relationships><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/header" Target="header1.xml"/></Relationships>"#.to_string(),
+            ),
+            ("word/document.xml", document),
+            ("word/header1.xml", header),
+            (
+                "customXml/item1.xml",
+                r#"<root xmlns="urn:data" xmlns:d="urn:data" d:version="2"><name>New name</name></root>"#.to_string(),
+            ),
+            (
+                "customXml/itemProps1.xml",
+                r#"<ds:datastoreItem ds:itemID="{A}" xmlns:ds="urn:ds"/>"#.to_string(),
+            ),
+        ]);
+        let part_names: Vec<String> = parts
+            .keys()
+            .map(|part_name| part_name.to_string())
+            .collect();
+
+        let analysis = analyze_data_bindings_in_parts(&part_names, &|part_name| {
+            parts
+                .get(part_name)
+                .cloned()
+                .ok_or_else(|| Error::invalid_package(part_name, "Missing part"))
+        })
+        .unwrap();
+
+        assert!(analysis.skipped_parts.is_empty());
+        let data_bindings = &analysis.data_bindings;
+        assert_eq!(data_bindings.len(), 3);
+
+        assert_eq!(data_bindings[0].part_name, "word/document.xml");
+        assert_eq!(data_bindings[0].tag.as_deref(), Some("name"));
+        assert_eq!(data_bindings[0].alias.as_deref(), Some("name title"));
+        assert_eq!(
+            data_bindings[0].custom_xml_part_name.as_deref(),
+            Some("customXml/item1.xml")
+        );
+        assert_eq!(data_bindings[0].text, "Old name");
+        assert_eq!(data_bindings[0].value.as_deref(), Some("New name"));
+
+        assert_eq!(data_bindings[1].custom_xml_part_name, None);
+        assert_eq!(data_bindings[1].value, None);
+        assert!(data_bindings[1].error.is_some());
+
+        // The store item ID is compared ignoring the case
+        assert_eq!(data_bindings[2].part_name, "word/header1.xml");
+        assert_eq!(data_bindings[2].value.as_deref(), Some("2"));
+    }
+}
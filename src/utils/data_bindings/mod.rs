@@ -0,0 +1,3 @@
+pub mod main;
+pub mod refresh;
+pub mod xpath;
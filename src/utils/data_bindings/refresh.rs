@@ -0,0 +1,371 @@
+use std::{collections::BTreeMap, fs, ops::Range, path::Path};
+
+use crate::utils::{
+    data_bindings::main::{
+        DataBinding, find_bound_content_controls, get_custom_xml_parts, get_data_binding,
+        get_document_part_names,
+    },
+    error::Error,
+    files::get_part_names_in_folder,
+    xml::{XmlEdit, XmlElement, XmlNode, XmlSpan, apply_xml_edits},
+};
+
+/// The elements of a run holding its text, see [`get_run_text`](crate::utils::data_bindings::main::get_run_text)
+const TEXT_ELEMENT_NAMES: [&str; 4] = ["t", "tab", "br", "cr"];
+
+/// Rewrite the text of the bound content controls whose custom XML data changed, so the document shows the new values
+/// without being opened in Word
+///
+/// The first text element of the content control gets the value, tabs and line breaks become `w:tab` and `w:br`, and
+/// the other text elements are removed so the runs keep their formatting. A content control nested in one that is
+/// refreshed is rewritten along with it. Bindings whose XPath doesn't select anything are left as is.
+///
+/// Returns the refreshed data bindings, their `text` is the text they showed before
+pub fn refresh_data_bindings(extracted_folder: &str) -> Result<Vec<DataBinding>, Error> {
+    let folder_path = Path::new(extracted_folder);
+    if !folder_path.is_dir() {
+        return Err(Error::invalid_package(
+            folder_path,
+            "The extracted folder does not exist",
+        ));
+    }
+
+    let part_names = get_part_names_in_folder(folder_path)?;
+    let read_part = |part_name: &str| {
+        let part_path = folder_path.join(part_name);
+        fs::read_to_string(&part_path).map_err(|e| Error::io(&part_path, e))
+    };
+    let custom_xml_parts = get_custom_xml_parts(&part_names, &read_part);
+
+    // * Find the stale content controls of every document part before writing any of them
+    let mut refreshed_data_bindings = Vec::new();
+    let mut updated_parts: Vec<(String, String)> = Vec::new();
+    for part_name in get_document_part_names(&read_part)? {
+        let content = read_part(&part_name)?;
+        let (root, span) = XmlElement::parse_with_spans(&content)
+            .map_err(|e| Error::custom_xml_parse(folder_path.join(&part_name), e))?;
+
+        let mut content_controls = Vec::new();
+        find_bound_content_controls(&root, &span, &mut content_controls);
+
+        let mut edits: Vec<XmlEdit> = Vec::new();
+        let mut refreshed_end = 0;
+        for (content_control, content_control_span) in content_controls {
+            // Already rewritten with the content control it's nested in
+            if content_control_span.range.start < refreshed_end {
+                continue;
+            }
+
+            let data_binding = get_data_binding(&part_name, content_control, &custom_xml_parts);
+            let Some(value) = &data_binding.value else {
+                continue;
+            };
+            if data_binding.error.is_some() || *value == data_binding.text {
+                continue;
+            }
+
+            get_content_control_edits(
+                &content,
+                content_control,
+                content_control_span,
+                value,
+                &mut edits,
+            );
+            refreshed_end = content_control_span.range.end;
+            refreshed_data_bindings.push(data_binding);
+        }
+
+        if !edits.is_empty() {
+            updated_parts.push((part_name, apply_xml_edits(&content, edits)));
+        }
+    }
+
+    // * Write the updated parts
+    for (part_name, content) in updated_parts {
+        let path = folder_path.join(part_name);
+        fs::write(&path, content).map_err(|e| Error::io(&path, e))?;
+    }
+
+    Ok(refreshed_data_bindings)
+}
+
+/// The edits showing the value in the content control instead of its current text
+fn get_content_control_edits(
+    source: &str,
+    content_control: &XmlElement,
+    span: &XmlSpan,
+    value: &str,
+    edits: &mut Vec<XmlEdit>,
+) {
+    let prefix = get_prefix(&content_control.tag);
+
+    // * The placeholder is replaced by the value
+    for (properties, properties_span) in get_child_elements(content_control, span, "sdtPr") {
+        for (_, placeholder_span) in
+            get_child_elements(properties, properties_span, "showingPlcHdr")
+        {
+            edits.push((
+                get_range_with_indentation(source, placeholder_span),
+                String::new(),
+            ));
+        }
+    }
+
+    let Some((content, content_span)) =
+        get_child_elements(content_control, span, "sdtContent").next()
+    else {
+        return;
+    };
+
+    let mut text_elements = Vec::new();
+    find_elements(
+        content,
+        content_span,
+        &TEXT_ELEMENT_NAMES,
+        &mut text_elements,
+    );
+
+    // * The first text element gets the value, keeping the attributes of a `w:t`
+    if let Some((_, first_text_span)) = text_elements.first() {
+        let text_attributes = text_elements
+            .iter()
+            .find(|(element, _)| element.local_name() == "t")
+            .map(|(element, _)| element.attributes.clone())
+            .unwrap_or_default();
+        edits.push((
+            first_text_span.range.clone(),
+            get_run_content_xml(&prefix, text_attributes, value),
+        ));
+        for (_, text_span) in &text_elements[1..] {
+            edits.push((text_span.range.clone(), String::new()));
+        }
+        return;
+    }
+
+    // * No text yet, it goes in a new run at the end of the first run, the first paragraph or the content itself
+    let run_content_xml = get_run_content_xml(&prefix, BTreeMap::new(), value);
+    let mut runs = Vec::new();
+    find_elements(content, content_span, &["r"], &mut runs);
+    if let Some((run, run_span)) = runs.first() {
+        edits.push(get_append_edit(source, run, run_span, &run_content_xml));
+        return;
+    }
+
+    let run_xml = format!("<{0}r>{1}</{0}r>", prefix, run_content_xml);
+    let mut paragraphs = Vec::new();
+    find_elements(content, content_span, &["p"], &mut paragraphs);
+    match paragraphs.first() {
+        Some((paragraph, paragraph_span)) => {
+            edits.push(get_append_edit(source, paragraph, paragraph_span, &run_xml))
+        }
+        None => edits.push(get_append_edit(source, content, content_span, &run_xml)),
+    }
+}
+
+/// The text elements of a run showing the value, tabs and line breaks are `w:tab` and `w:br` elements
+fn get_run_content_xml(
+    prefix: &str,
+    text_attributes: BTreeMap<String, String>,
+    value: &str,
+) -> String {
+    let mut xml = String::new();
+    let mut segment = String::new();
+    let push_text = |xml: &mut String, segment: &mut String| {
+        if segment.is_empty() {
+            return;
+        }
+
+        let mut attributes = text_attributes.clone();
+        attributes.remove("xml:space");
+        // Word drops the leading and trailing spaces of a text unless they are preserved
+        if segment.starts_with(char::is_whitespace) || segment.ends_with(char::is_whitespace) {
+            attributes.insert("xml:space".to_string(), "preserve".to_string());
+        }
+        let text_element = XmlElement {
+            tag: format!("{}t", prefix),
+            attributes,
+            children: vec![XmlNode::Text(std::mem::take(segment))],
+        };
+        xml.push_str(&text_element.to_xml_string());
+    };
+
+    for character in value.chars() {
+        match character {
+            '\t' => {
+                push_text(&mut xml, &mut segment);
+                xml.push_str(&format!("<{}tab/>", prefix));
+            }
+            '\n' => {
+                push_text(&mut xml, &mut segment);
+                xml.push_str(&format!("<{}br/>", prefix));
+            }
+            '\r' => {}
+            _ => segment.push(character),
+        }
+    }
+    push_text(&mut xml, &mut segment);
+
+    xml
+}
+
+/// Append the XML to the content of the element, an empty element (`<w:r/>`) gets an end tag
+fn get_append_edit(source: &str, element: &XmlElement, span: &XmlSpan, xml: &str) -> XmlEdit {
+    match &span.content {
+        Some(content) => (content.end..content.end, xml.to_string()),
+        None => {
+            let start_tag = source[span.range.start..span.range.end - "/>".len()].trim_end();
+            (
+                span.range.clone(),
+                format!("{}>{}</{}>", start_tag, xml, element.tag),
+            )
+        }
+    }
+}
+
+/// The range of the element along with the whitespace before it
+fn get_range_with_indentation(source: &str, span: &XmlSpan) -> Range<usize> {
+    source[..span.range.start].trim_end().len()..span.range.end
+}
+
+/// The namespace prefix of a tag with its colon, e.g. `w:` for `w:sdt`
+fn get_prefix(tag: &str) -> String {
+    tag.rsplit_once(':')
+        .map(|(prefix, _)| format!("{}:", prefix))
+        .unwrap_or_default()
+}
+
+fn get_child_elements<'a>(
+    element: &'a XmlElement,
+    span: &'a XmlSpan,
+    local_name: &'a str,
+) -> impl Iterator<Item = (&'a XmlElement, &'a XmlSpan)> {
+    element
+        .children
+        .iter()
+        .zip(&span.children)
+        .filter_map(move |(child, child_span)| match child {
+            XmlNode::Element(child) if child.local_name() == local_name => {
+                Some((child, child_span))
+            }
+            _ => None,
+        })
+}
+
+/// The descendants with one of the local names in document order, without looking inside them
+fn find_elements<'a>(
+    element: &'a XmlElement,
+    span: &'a XmlSpan,
+    local_names: &[&str],
+    elements: &mut Vec<(&'a XmlElement, &'a XmlSpan)>,
+) {
+    for (child, child_span) in element.children.iter().zip(&span.children) {
+        let XmlNode::Element(child) = child else {
+            continue;
+        };
+
+        if local_names.contains(&child.local_name()) {
+            elements.push((child, child_span));
+        } else {
+            find_elements(child, child_span, local_names, elements);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::zip_utils::main::CONTENT_TYPES_PART_NAME;
+    use tempfile::tempdir;
+
+    fn refresh_content_control(content_control: &str, value: &str) -> String {
+        let (root, span) = XmlElement::parse_with_spans(content_control).unwrap();
+        let mut edits = Vec::new();
+        get_content_control_edits(content_control, &root, &span, value, &mut edits);
+
+        apply_xml_edits(content_control, edits)
+    }
+
+    #[test]
+    fn test_get_content_control_edits() {
+        // The first run gets the value, the others lose their text
+        assert_eq!(
+            refresh_content_control(
+                r#"<w:sdt><w:sdtPr><w:showingPlcHdr/></w:sdtPr><w:sdtContent><w:r><w:rPr><w:b/></w:rPr><w:t xml:space="preserve">Old </w:t></w:r><w:r><w:tab/><w:t>text</w:t></w:r></w:sdtContent></w:sdt>"#,
+                "New\tvalue & more"
+            ),
+            r#"<w:sdt><w:sdtPr></w:sdtPr><w:sdtContent><w:r><w:rPr><w:b/></w:rPr><w:t>New</w:t><w:tab/><w:t>value &amp; more</w:t></w:r><w:r></w:r></w:sdtContent></w:sdt>"#
+        );
+
+        // A content control without text gets a run in its paragraph
+        assert_eq!(
+            refresh_content_control(
+                "<w:sdt><w:sdtContent><w:p><w:pPr/></w:p></w:sdtContent></w:sdt>",
+                " padded"
+            ),
+            r#"<w:sdt><w:sdtContent><w:p><w:pPr/><w:r><w:t xml:space="preserve"> padded</w:t></w:r></w:p></w:sdtContent></w:sdt>"#
+        );
+        assert_eq!(
+            refresh_content_control("<w:sdt><w:sdtContent/></w:sdt>", "a\nb"),
+            "<w:sdt><w:sdtContent><w:r><w:t>a</w:t><w:br/><w:t>b</w:t></w:r></w:sdtContent></w:sdt>"
+        );
+    }
+
+    #[test]
+    fn test_refresh_data_bindings() {
+        let temp_dir = tempdir().unwrap();
+        let extracted_folder = temp_dir.path();
+        fs::create_dir_all(extracted_folder.join("word")).unwrap();
+        fs::create_dir_all(extracted_folder.join("customXml")).unwrap();
+        fs::write(
+            extracted_folder.join(CONTENT_TYPES_PART_NAME),
+            r#"<Types><Override PartName="/word/document.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml"/></Types>"#,
+        )
+        .unwrap();
+        fs::write(
+            extracted_folder.join("word/document.xml"),
+            r#"<w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">
+    <w:body>
+        <w:sdt>
+            <w:sdtPr><w:dataBinding w:xpath="/root[1]/name[1]" w:storeItemID="{A}"/></w:sdtPr>
+            <w:sdtContent><w:p><w:r><w:t>Old name</w:t></w:r></w:p></w:sdtContent>
+        </w:sdt>
+        <w:sdt>
+            <w:sdtPr><w:dataBinding w:xpath="/root[1]/missing[1]" w:storeItemID="{A}"/></w:sdtPr>
+            <w:sdtContent><w:p><w:r><w:t>Kept</w:t></w:r></w:p></w:sdtContent>
+        </w:sdt>
+    </w:body>
+</w:document>"#,
+        )
+        .unwrap();
+        fs::write(
+            extracted_folder.join("customXml/item1.xml"),
+            "<root><name>New name</name></root>",
+        )
+        .unwrap();
+        fs::write(
+            extracted_folder.join("customXml/itemProps1.xml"),
+            r#"<ds:datastoreItem ds:itemID="{A}" xmlns:ds="urn:ds"/>"#,
+        )
+        .unwrap();
+
+        let extracted_folder_str = extracted_folder.to_str().unwrap();
+        let refreshed_data_bindings = refresh_data_bindings(extracted_folder_str).unwrap();
+
+        assert_eq!(refreshed_data_bindings.len(), 1);
+        assert_eq!(refreshed_data_bindings[0].text, "Old name");
+        assert_eq!(
+            refreshed_data_bindings[0].value.as_deref(),
+            Some("New name")
+        );
+        let document = fs::read_to_string(extracted_folder.join("word/document.xml")).unwrap();
+        assert!(document.contains("<w:t>New name</w:t>"));
+        assert!(document.contains("<w:t>Kept</w:t>"));
+
+        // Nothing is stale anymore
+        assert!(
+            refresh_data_bindings(extracted_folder_str)
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
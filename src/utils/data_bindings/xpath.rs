@@ -0,0 +1,272 @@
+use fancy_regex::Regex;
+use std::collections::HashMap;
+
+use crate::utils::xml::{XmlElement, XmlNode, get_local_name};
+
+/// Namespace URIs by prefix, the default namespace has an empty prefix
+type Namespaces = HashMap<String, String>;
+
+/// A step of an absolute location path
+#[derive(Debug, PartialEq, Eq)]
+enum Step<'a> {
+    /// `ns0:item[2]`, the position is 1-based and counts the matching siblings
+    Element {
+        name: &'a str,
+        position: Option<usize>,
+    },
+    /// `@ns0:id`, only as the last step
+    Attribute(&'a str),
+    /// `text()`, only as the last step
+    Text,
+}
+
+/// The namespace URIs of the prefixes used in an XPath, from the `w:prefixMappings` of a data binding
+///
+/// e.g. `xmlns:ns0='http://example.com/data' xmlns:ns1='urn:other'`
+pub fn parse_prefix_mappings(prefix_mappings: &str) -> HashMap<String, String> {
+    let mapping_re = Regex::new(r#"xmlns:([^\s=]+)\s*=\s*(["'])(.*?)\2"#).unwrap();
+
+    mapping_re
+        .captures_iter(prefix_mappings)
+        .flatten()
+        .filter_map(|mapping| Some((mapping.get(1)?.as_str(), mapping.get(3)?.as_str())))
+        .map(|(prefix, uri)| (prefix.to_string(), uri.to_string()))
+        .collect()
+}
+
+/// Evaluate the XPath of a data binding against the root element of a custom XML part
+///
+/// Returns the string value of the first node it selects, `None` if it doesn't select any. Only the absolute location
+/// paths Word writes are supported: element steps with an optional position (`/ns0:root[1]/ns0:item[2]`), with an
+/// attribute (`/@ns0:id`) or a `text()` step at the end. Prefixes are matched by their namespace URI, not by name.
+pub fn evaluate_xpath(
+    root: &XmlElement,
+    xpath: &str,
+    prefix_mappings: &HashMap<String, String>,
+) -> Result<Option<String>, String> {
+    let steps = parse_xpath(xpath)?;
+    let unsupported = || format!("Unsupported XPath {}", xpath);
+
+    // The root is the only child of the document node
+    let mut context: Vec<(&XmlElement, Namespaces)> =
+        vec![(root, get_namespaces(root, &HashMap::new()))];
+    let mut is_root_step = true;
+    for (index, step) in steps.iter().enumerate() {
+        let is_last_step = index == steps.len() - 1;
+        match step {
+            Step::Element { name, position } => {
+                let expected_name = resolve_name(name, prefix_mappings)?;
+                context = if is_root_step {
+                    context
+                        .into_iter()
+                        .filter(|(element, namespaces)| {
+                            position.is_none_or(|position| position == 1)
+                                && is_element_match(element, namespaces, &expected_name)
+                        })
+                        .collect()
+                } else {
+                    context
+                        .iter()
+                        .flat_map(|(parent, namespaces)| {
+                            let matches = parent
+                                .children
+                                .iter()
+                                .filter_map(|child| match child {
+                                    XmlNode::Element(element) => Some(element),
+                                    _ => None,
+                                })
+                                .map(|element| (element, get_namespaces(element, namespaces)))
+                                .filter(|(element, namespaces)| {
+                                    is_element_match(element, namespaces, &expected_name)
+                                });
+                            match position {
+                                Some(position) => matches.skip(position - 1).take(1).collect(),
+                                None => matches.collect::<Vec<_>>(),
+                            }
+                        })
+                        .collect()
+                };
+                is_root_step = false;
+            }
+            Step::Attribute(name) if is_last_step && !is_root_step => {
+                let expected_name = resolve_name(name, prefix_mappings)?;
+                let value = context.iter().find_map(|(element, namespaces)| {
+                    element
+                        .attributes
+                        .iter()
+                        .filter(|(attribute_name, _)| !is_namespace_declaration(attribute_name))
+                        .find(|(attribute_name, _)| {
+                            // Unprefixed attributes don't take the default namespace
+                            let namespace = match attribute_name.split_once(':') {
+                                Some((prefix, _)) => namespaces.get(prefix).map(String::as_str),
+                                None => None,
+                            };
+                            (namespace, get_local_name(attribute_name))
+                                == (expected_name.0.as_deref(), expected_name.1)
+                        })
+                        .map(|(_, value)| value.clone())
+                });
+                return Ok(value);
+            }
+            Step::Text if is_last_step && !is_root_step => {
+                let value = context.first().and_then(|(element, _)| {
+                    element.children.iter().find_map(|child| match child {
+                        XmlNode::Text(text) => Some(text.clone()),
+                        _ => None,
+                    })
+                });
+                return Ok(value);
+            }
+            _ => return Err(unsupported()),
+        }
+    }
+
+    Ok(context.first().map(|(element, _)| element.get_text()))
+}
+
+fn parse_xpath(xpath: &str) -> Result<Vec<Step<'_>>, String> {
+    let unsupported = || format!("Unsupported XPath {}", xpath);
+    let Some(path) = xpath.trim().strip_prefix('/') else {
+        return Err(unsupported());
+    };
+
+    path.split('/')
+        .map(|step| {
+            if step == "text()" {
+                return Ok(Step::Text);
+            }
+            if let Some(name) = step.strip_prefix('@') {
+                return is_name(name)
+                    .then_some(Step::Attribute(name))
+                    .ok_or_else(unsupported);
+            }
+
+            let (name, position) = match step.split_once('[') {
+                Some((name, predicate)) => {
+                    let position = predicate
+                        .strip_suffix(']')
+                        .and_then(|position| position.trim().parse::<usize>().ok())
+                        .filter(|position| *position > 0)
+                        .ok_or_else(unsupported)?;
+                    (name, Some(position))
+                }
+                None => (step, None),
+            };
+            if !is_name(name) && name != "*" {
+                return Err(unsupported());
+            }
+            Ok(Step::Element { name, position })
+        })
+        .collect()
+}
+
+fn is_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.split(':').count() <= 2
+        && name.split(':').all(|part| {
+            !part.is_empty()
+                && part
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || "_-.".contains(c))
+        })
+}
+
+/// The namespace URI and the local name of a name in the XPath, `*` is `(None, "*")`
+fn resolve_name<'a>(
+    name: &'a str,
+    prefix_mappings: &HashMap<String, String>,
+) -> Result<(Option<String>, &'a str), String> {
+    match name.split_once(':') {
+        Some((prefix, local_name)) => match prefix_mappings.get(prefix) {
+            Some(uri) => Ok((Some(uri.clone()), local_name)),
+            None => Err(format!(
+                "The prefix {} isn't in the prefix mappings",
+                prefix
+            )),
+        },
+        None => Ok((None, name)),
+    }
+}
+
+fn is_element_match(
+    element: &XmlElement,
+    namespaces: &Namespaces,
+    (expected_namespace, expected_local_name): &(Option<String>, &str),
+) -> bool {
+    if *expected_local_name == "*" {
+        return true;
+    }
+
+    let namespace = match element.tag.split_once(':') {
+        Some((prefix, _)) => namespaces.get(prefix),
+        None => namespaces.get(""),
+    };
+    namespace == expected_namespace.as_ref() && element.local_name() == *expected_local_name
+}
+
+/// The namespaces in scope of the element, its parent's with its own `xmlns` declarations
+fn get_namespaces(element: &XmlElement, parent_namespaces: &Namespaces) -> Namespaces {
+    let mut namespaces = parent_namespaces.clone();
+    for (name, value) in &element.attributes {
+        if name == "xmlns" {
+            namespaces.insert(String::new(), value.clone());
+        } else if let Some(prefix) = name.strip_prefix("xmlns:") {
+            namespaces.insert(prefix.to_string(), value.clone());
+        }
+    }
+    namespaces
+}
+
+fn is_namespace_declaration(attribute_name: &str) -> bool {
+    attribute_name == "xmlns" || attribute_name.starts_with("xmlns:")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evaluate_xpath() {
+        let root = XmlElement::parse(
+            r#"<root xmlns="urn:data" xmlns:x="urn:extra">
+    <item id="1">First</item>
+    <item x:id="2">Second <b>part</b></item>
+    <x:note>Note</x:note>
+</root>"#,
+        )
+        .unwrap();
+        let prefix_mappings = parse_prefix_mappings("xmlns:ns0='urn:data' xmlns:ns1=\"urn:extra\"");
+        let evaluate = |xpath: &str| evaluate_xpath(&root, xpath, &prefix_mappings);
+
+        assert_eq!(
+            evaluate("/ns0:root[1]/ns0:item[1]"),
+            Ok(Some("First".to_string()))
+        );
+        assert_eq!(
+            evaluate("/ns0:root/ns0:item[2]"),
+            Ok(Some("Second part".to_string()))
+        );
+        assert_eq!(
+            evaluate("/ns0:root/ns0:item[2]/text()"),
+            Ok(Some("Second ".to_string()))
+        );
+        assert_eq!(
+            evaluate("/ns0:root/ns0:item[1]/@id"),
+            Ok(Some("1".to_string()))
+        );
+        assert_eq!(
+            evaluate("/ns0:root/ns0:item[2]/@ns1:id"),
+            Ok(Some("2".to_string()))
+        );
+        assert_eq!(evaluate("/ns0:root/ns1:note"), Ok(Some("Note".to_string())));
+
+        // Nothing selected
+        assert_eq!(evaluate("/ns0:root/ns0:item[3]"), Ok(None));
+        assert_eq!(evaluate("/ns0:root/item"), Ok(None));
+        assert_eq!(evaluate("/ns1:root"), Ok(None));
+
+        assert!(evaluate("//ns0:item").is_err());
+        assert!(evaluate("/ns0:root/ns0:item[@id='1']").is_err());
+        assert!(evaluate("/ns2:root").is_err());
+    }
+}
@@ -0,0 +1,179 @@
+use std::{
+    fmt, io,
+    path::{Path, PathBuf},
+};
+
+use crate::utils::{sync_custom_xml::schema::SchemaViolation, validate::main::ValidationIssue};
+
+/// Everything that can go wrong while working with a package, each variant carries the path involved
+#[derive(Debug)]
+pub enum Error {
+    /// Reading or writing a file or folder failed
+    Io { path: PathBuf, source: io::Error },
+    /// Reading or writing a zip archive failed
+    Zip {
+        path: PathBuf,
+        source: zip::result::ZipError,
+    },
+    /// Reading or writing a JSON file failed
+    Json {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+    /// The packed file or the extracted folder doesn't have the expected layout
+    InvalidPackage { path: PathBuf, reason: String },
+    /// A custom XML part doesn't have a supported content
+    CustomXmlParse { path: PathBuf, reason: String },
+    /// A `.xml` or `.rels` part is not well-formed, the line and column are 1-based
+    XmlSyntax {
+        path: PathBuf,
+        line: usize,
+        column: usize,
+        reason: String,
+    },
+    /// The package structure has validation issues, see [`crate::validate_folder`]
+    Validation {
+        path: PathBuf,
+        issues: Vec<ValidationIssue>,
+    },
+    /// The JSON content in customXml.json doesn't match its registered schema, see [`crate::CustomXmlSchemas`]
+    SchemaValidation {
+        path: PathBuf,
+        violations: Vec<SchemaViolation>,
+    },
+}
+
+impl Error {
+    pub fn io(path: impl AsRef<Path>, source: io::Error) -> Self {
+        Self::Io {
+            path: path.as_ref().to_path_buf(),
+            source,
+        }
+    }
+
+    pub fn zip(path: impl AsRef<Path>, source: zip::result::ZipError) -> Self {
+        Self::Zip {
+            path: path.as_ref().to_path_buf(),
+            source,
+        }
+    }
+
+    pub fn json(path: impl AsRef<Path>, source: serde_json::Error) -> Self {
+        Self::Json {
+            path: path.as_ref().to_path_buf(),
+            source,
+        }
+    }
+
+    pub fn invalid_package(path: impl AsRef<Path>, reason: impl Into<String>) -> Self {
+        Self::InvalidPackage {
+            path: path.as_ref().to_path_buf(),
+            reason: reason.into(),
+        }
+    }
+
+    pub fn custom_xml_parse(path: impl AsRef<Path>, reason: impl Into<String>) -> Self {
+        Self::CustomXmlParse {
+            path: path.as_ref().to_path_buf(),
+            reason: reason.into(),
+        }
+    }
+
+    pub fn xml_syntax(
+        path: impl AsRef<Path>,
+        line: usize,
+        column: usize,
+        reason: impl Into<String>,
+    ) -> Self {
+        Self::XmlSyntax {
+            path: path.as_ref().to_path_buf(),
+            line,
+            column,
+            reason: reason.into(),
+        }
+    }
+
+    /// The path of the file or folder the error is about
+    pub fn path(&self) -> &Path {
+        match self {
+            Self::Io { path, .. }
+            | Self::Zip { path, .. }
+            | Self::Json { path, .. }
+            | Self::InvalidPackage { path, .. }
+            | Self::CustomXmlParse { path, .. }
+            | Self::XmlSyntax { path, .. }
+            | Self::Validation { path, .. }
+            | Self::SchemaValidation { path, .. } => path,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io { path, source } => write!(f, "{}: {}", path.display(), source),
+            Self::Zip { path, source } => {
+                write!(f, "Invalid zip archive {}: {}", path.display(), source)
+            }
+            Self::Json { path, source } => write!(f, "Invalid JSON {}: {}", path.display(), source),
+            Self::InvalidPackage { path, reason } => {
+                write!(f, "Invalid package {}: {}", path.display(), reason)
+            }
+            Self::CustomXmlParse { path, reason } => {
+                write!(f, "Unsupported custom XML {}: {}", path.display(), reason)
+            }
+            Self::XmlSyntax {
+                path,
+                line,
+                column,
+                reason,
+            } => write!(
+                f,
+                "Malformed XML {}:{}:{}: {}",
+                path.display(),
+                line,
+                column,
+                reason
+            ),
+            Self::Validation { path, issues } => {
+                write!(
+                    f,
+                    "Invalid package {}: {} validation issue(s)",
+                    path.display(),
+                    issues.len()
+                )?;
+                for issue in issues {
+                    write!(f, "\n  - {}", issue)?;
+                }
+                Ok(())
+            }
+            Self::SchemaValidation { path, violations } => {
+                write!(
+                    f,
+                    "Invalid custom XML data {}: {} schema violation(s)",
+                    path.display(),
+                    violations.len()
+                )?;
+                for violation in violations {
+                    write!(f, "\n  - {}", violation)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io { source, .. } => Some(source),
+            Self::Zip { source, .. } => Some(source),
+            Self::Json { source, .. } => Some(source),
+            Self::InvalidPackage { .. }
+            | Self::CustomXmlParse { .. }
+            | Self::XmlSyntax { .. }
+            | Self::Validation { .. }
+            | Self::SchemaValidation { .. } => None,
+        }
+    }
+}
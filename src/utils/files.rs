@@ -1,10 +1,10 @@
+use crate::utils::error::Error;
 use crate::utils::types::EXTRACTED_FOLDER_NAME;
 use crate::utils::types::FilePathInfo;
 use crate::utils::types::ZipFolder;
 use fancy_regex::Regex;
 use serde::Serialize;
 use serde::de::DeserializeOwned;
-use std::error::Error;
 use std::fs::File;
 use std::fs::{self, DirEntry};
 use std::io;
@@ -44,6 +44,25 @@ pub fn visit_dirs(dir: &Path, file_callback: &mut dyn FnMut(&DirEntry)) -> io::R
     Ok(())
 }
 
+/// The names of all the files in the folder relative to it, always with `/` as the separator like the part names of an archive
+pub fn get_part_names_in_folder(folder_path: &Path) -> Result<Vec<String>, Error> {
+    let mut part_names: Vec<String> = Vec::new();
+    visit_dirs(folder_path, &mut |entry| {
+        let path = entry.path();
+        if let Ok(relative_path) = path.strip_prefix(folder_path) {
+            let part_name = relative_path
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            part_names.push(part_name);
+        }
+    })
+    .map_err(|e| Error::io(folder_path, e))?;
+
+    Ok(part_names)
+}
+
 /// Check whether the given extension is an image extension
 pub fn is_image_extension(extension: &str) -> bool {
     matches!(
@@ -52,18 +71,17 @@ pub fn is_image_extension(extension: &str) -> bool {
     )
 }
 
-pub fn write_struct_to_json<T: Serialize>(data: &T, file_path: &str) -> Result<(), std::io::Error> {
-    let json = serde_json::to_string_pretty(data)?;
-    fs::write(file_path, json)
+pub fn write_struct_to_json<T: Serialize>(data: &T, file_path: &str) -> Result<(), Error> {
+    let json = serde_json::to_string_pretty(data).map_err(|e| Error::json(file_path, e))?;
+    fs::write(file_path, json).map_err(|e| Error::io(file_path, e))
 }
 
 /// <https://docs.rs/serde_json/latest/serde_json/fn.from_reader.html#example>
-pub fn read_struct_from_json<T: DeserializeOwned>(file_path: &str) -> Result<T, Box<dyn Error>> {
-    let file = File::open(file_path)?;
+pub fn read_struct_from_json<T: DeserializeOwned>(file_path: &str) -> Result<T, Error> {
+    let file = File::open(file_path).map_err(|e| Error::io(file_path, e))?;
     let reader = BufReader::new(file);
 
-    let data = serde_json::from_reader(reader)?;
-    Ok(data)
+    serde_json::from_reader(reader).map_err(|e| Error::json(file_path, e))
 }
 
 pub fn get_file_size_in_kb_from_bytes(file_size: u64) -> f64 {
@@ -174,7 +192,8 @@ mod tests {
         drop(file);
 
         // Create FilePathInfo from the temp file
-        let file_path_info = FilePathInfo::new(temp_file.to_string_lossy().to_string());
+        let file_path_info = FilePathInfo::new(temp_file.to_string_lossy().to_string())
+            .expect("Failed to read the temp file info");
 
         // Test get_output_folder
         let zip_folder = get_output_folder(&file_path_info);
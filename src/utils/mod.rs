@@ -1,10 +1,14 @@
 pub mod analyze_custom_xml;
-pub mod ensure_ooxml_exist;
-pub mod file_watcher;
+pub mod content_types;
+pub mod data_bindings;
+pub mod error;
 pub mod files;
-pub mod input_utils;
-pub mod print_utils;
+pub mod ooxml_package;
+pub mod package;
+pub mod relationships;
 pub mod summarize;
 pub mod sync_custom_xml;
 pub mod types;
+pub mod validate;
+pub mod xml;
 pub mod zip_utils;
@@ -0,0 +1,263 @@
+use std::{
+    fs::File,
+    io::{Cursor, Read, Seek, SeekFrom, Write},
+    path::{Component, Path, PathBuf},
+};
+
+use zip::{ZipArchive, ZipWriter};
+
+use crate::utils::{
+    error::Error,
+    zip_utils::main::{Compression, get_zip_file_options},
+};
+
+/// A single part (file) inside an OOXML package
+#[derive(Debug, Clone)]
+pub struct OoxmlPart {
+    /// Name of the part inside the archive, e.g. `word/document.xml`
+    pub name: String,
+    pub data: Vec<u8>,
+}
+
+/// An OOXML package (`.docx`, `.xlsx`, `.pptx`, etc) loaded in memory straight from the zip archive
+///
+/// This doesn't need an extracted folder on disk, parts are read and replaced by their name inside the archive
+#[derive(Debug, Clone)]
+pub struct OoxmlPackage {
+    /// Path of the packed file, used to describe the package in results and errors
+    pub path: PathBuf,
+    /// Size of the packed file in bytes
+    pub size: u64,
+    /// The parts in the same order as the archive entries
+    parts: Vec<OoxmlPart>,
+}
+
+impl OoxmlPackage {
+    /// Load the packed file at the given path into memory
+    pub fn open(file_path: impl AsRef<Path>) -> Result<Self, Error> {
+        let file_path = file_path.as_ref();
+        let file = File::open(file_path).map_err(|e| Error::io(file_path, e))?;
+
+        Self::from_reader(file, file_path)
+    }
+
+    /// Load a package from any reader, `path` is only used to describe the package in results and errors
+    ///
+    /// A part name that would point outside a folder the package is extracted to is an [`Error::InvalidPackage`],
+    /// see [`is_enclosed_part_name`]
+    pub fn from_reader<R: Read + Seek>(
+        mut reader: R,
+        path: impl AsRef<Path>,
+    ) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let size = reader
+            .seek(SeekFrom::End(0))
+            .map_err(|e| Error::io(path, e))?;
+        reader.rewind().map_err(|e| Error::io(path, e))?;
+
+        let mut archive = ZipArchive::new(reader).map_err(|e| Error::zip(path, e))?;
+
+        let mut parts = Vec::with_capacity(archive.len());
+        for index in 0..archive.len() {
+            let mut entry = archive.by_index(index).map_err(|e| Error::zip(path, e))?;
+            // Folders don't carry any data, they're implied by the part names
+            if entry.is_dir() {
+                continue;
+            }
+            if !is_enclosed_part_name(entry.name()) {
+                return Err(Error::invalid_package(
+                    path,
+                    format!("The part name {} points outside the package", entry.name()),
+                ));
+            }
+
+            let mut data = Vec::with_capacity(entry.size() as usize);
+            entry
+                .read_to_end(&mut data)
+                .map_err(|e| Error::io(path.join(entry.name()), e))?;
+
+            parts.push(OoxmlPart {
+                name: entry.name().to_string(),
+                data,
+            });
+        }
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            size,
+            parts,
+        })
+    }
+
+    /// All the parts in the package, in the archive order
+    pub fn parts(&self) -> &[OoxmlPart] {
+        &self.parts
+    }
+
+    /// The names of all the parts in the package, in the archive order
+    pub fn part_names(&self) -> impl Iterator<Item = &str> {
+        self.parts.iter().map(|part| part.name.as_str())
+    }
+
+    pub fn has_part(&self, name: &str) -> bool {
+        self.parts.iter().any(|part| part.name == name)
+    }
+
+    /// Read the raw content of a part, `None` if there is no part with that name
+    pub fn read_part(&self, name: &str) -> Option<&[u8]> {
+        self.parts
+            .iter()
+            .find(|part| part.name == name)
+            .map(|part| part.data.as_slice())
+    }
+
+    /// Read the content of a part as UTF-8 text
+    pub fn read_part_to_string(&self, name: &str) -> Result<String, Error> {
+        let data = self.read_part(name).ok_or_else(|| {
+            Error::invalid_package(self.path.join(name), "The part does not exist")
+        })?;
+
+        String::from_utf8(data.to_vec()).map_err(|e| {
+            Error::io(
+                self.path.join(name),
+                std::io::Error::new(std::io::ErrorKind::InvalidData, e),
+            )
+        })
+    }
+
+    /// Replace the content of a part, the part is added at the end if it doesn't exist yet
+    pub fn replace_part(&mut self, name: &str, data: Vec<u8>) {
+        match self.parts.iter_mut().find(|part| part.name == name) {
+            Some(part) => part.data = data,
+            None => self.parts.push(OoxmlPart {
+                name: name.to_string(),
+                data,
+            }),
+        }
+    }
+
+    /// Write the package as a zip archive into the writer, keeping the order of the parts
+    pub fn write_to<W: Write + Seek>(&self, writer: W) -> Result<W, Error> {
+        let mut zip_writer = ZipWriter::new(writer);
+        let options = get_zip_file_options(Compression::default());
+
+        for part in &self.parts {
+            zip_writer
+                .start_file(&part.name, options)
+                .map_err(|e| Error::zip(self.path.join(&part.name), e))?;
+            zip_writer
+                .write_all(&part.data)
+                .map_err(|e| Error::io(self.path.join(&part.name), e))?;
+        }
+
+        zip_writer.finish().map_err(|e| Error::zip(&self.path, e))
+    }
+
+    /// Save the package as a zip archive at the given path
+    pub fn save(&self, output_file_path: impl AsRef<Path>) -> Result<(), Error> {
+        let output_file_path = output_file_path.as_ref();
+        let bytes = self.write_to(Cursor::new(Vec::new()))?.into_inner();
+
+        std::fs::write(output_file_path, bytes).map_err(|e| Error::io(output_file_path, e))
+    }
+}
+
+/// Whether the part name stays inside the folder it's joined to, i.e. it has no `..`, `.`, root or prefix component
+pub fn is_enclosed_part_name(name: &str) -> bool {
+    Path::new(name)
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)))
+}
+
+/// Build a zip archive in memory with the given part names and contents
+#[cfg(test)]
+pub(crate) fn build_test_archive(parts: &[(&str, &str)]) -> Vec<u8> {
+    use zip::write::SimpleFileOptions;
+
+    let mut zip_writer = ZipWriter::new(Cursor::new(Vec::new()));
+    for (name, content) in parts {
+        zip_writer
+            .start_file(*name, SimpleFileOptions::default())
+            .unwrap();
+        zip_writer.write_all(content.as_bytes()).unwrap();
+    }
+    zip_writer.finish().unwrap().into_inner()
+}
+
+/// Load a package built in memory with the given part names and contents, `path` is only used to describe it
+#[cfg(test)]
+pub(crate) fn build_test_package(path: impl AsRef<Path>, parts: &[(&str, &str)]) -> OoxmlPackage {
+    OoxmlPackage::from_reader(Cursor::new(build_test_archive(parts)), path).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_reader_lists_parts_in_archive_order() {
+        let archive = build_test_archive(&[
+            ("[Content_Types].xml", "<Types/>"),
+            ("word/document.xml", "<w:document/>"),
+            ("customXml/item1.xml", "<root>{}</root>"),
+        ]);
+
+        let package = OoxmlPackage::from_reader(Cursor::new(archive), "test.docx").unwrap();
+
+        assert_eq!(
+            package.part_names().collect::<Vec<_>>(),
+            vec![
+                "[Content_Types].xml",
+                "word/document.xml",
+                "customXml/item1.xml"
+            ]
+        );
+        assert!(package.has_part("word/document.xml"));
+        assert!(!package.has_part("word/missing.xml"));
+        assert_eq!(
+            package.read_part_to_string("customXml/item1.xml").unwrap(),
+            "<root>{}</root>"
+        );
+        assert!(package.read_part_to_string("word/missing.xml").is_err());
+    }
+
+    #[test]
+    fn test_replace_part_round_trip() {
+        let archive = build_test_archive(&[
+            ("[Content_Types].xml", "<Types/>"),
+            ("customXml/item1.xml", "<root>{}</root>"),
+        ]);
+        let mut package = OoxmlPackage::from_reader(Cursor::new(archive), "test.docx").unwrap();
+
+        package.replace_part("customXml/item1.xml", b"<root>{\"a\":1}</root>".to_vec());
+        package.replace_part("customXml/item2.xml", b"<root>{}</root>".to_vec());
+
+        let written = package.write_to(Cursor::new(Vec::new())).unwrap();
+        let reloaded = OoxmlPackage::from_reader(written, "test.docx").unwrap();
+
+        assert_eq!(
+            reloaded.read_part_to_string("customXml/item1.xml").unwrap(),
+            "<root>{\"a\":1}</root>"
+        );
+        // New parts are appended after the existing ones
+        assert_eq!(reloaded.part_names().last(), Some("customXml/item2.xml"));
+    }
+
+    #[test]
+    fn test_from_reader_err_on_part_outside_package() {
+        for name in ["../../pwned.txt", "/pwned.txt", "word/../../pwned.txt"] {
+            let archive = build_test_archive(&[("word/document.xml", "<w:document/>"), (name, "")]);
+            let result = OoxmlPackage::from_reader(Cursor::new(archive), "test.docx");
+            assert!(
+                matches!(result, Err(Error::InvalidPackage { .. })),
+                "{name}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_from_reader_err_on_invalid_zip() {
+        let result = OoxmlPackage::from_reader(Cursor::new(b"not a zip".to_vec()), "test.docx");
+        assert!(matches!(result, Err(Error::Zip { .. })));
+    }
+}
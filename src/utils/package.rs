@@ -0,0 +1,157 @@
+use std::{fs::File, path::Path};
+
+use crate::utils::{
+    analyze_custom_xml::main::{CustomXmlAnalysis, analyze_custom_xml},
+    data_bindings::main::{DataBindingAnalysis, analyze_data_bindings},
+    error::Error,
+    files::{get_output_folder, write_struct_to_json},
+    ooxml_package::OoxmlPackage,
+    summarize::main::{SummarizeData, summarize},
+    types::{CUSTOM_XML_FILE_NAME, FilePathInfo, MANIFEST_FILE_NAME, SUMMARY_FILE_NAME, ZipFolder},
+    validate::main::{ValidationReport, validate_folder},
+    zip_utils::main::{extract_zip, extract_zip_from_reader},
+};
+
+/// A packed OOXML file (`.docx`, `.xlsx`, etc) together with the folders it's extracted to
+///
+/// The layout on disk is
+/// ```text
+/// <file_path>/<file_name>.docx
+/// <file_path>/<file_name>/              <- root folder
+/// <file_path>/<file_name>/extracted/    <- extracted folder
+/// <file_path>/<file_name>/summary.json
+/// <file_path>/<file_name>/customXml.json
+/// <file_path>/<file_name>/manifest.json
+/// ```
+#[derive(Debug, Clone)]
+pub struct Package {
+    /// Info about the packed file
+    pub file_path_info: FilePathInfo,
+    /// The root folder that contains the extracted folder and the generated json files
+    pub root_folder: String,
+    /// The folder where the packed file is extracted to
+    pub extracted_folder: String,
+}
+
+impl Package {
+    /// Open the packed file at the given path, this doesn't extract anything yet
+    pub fn open(file_path: &str) -> Result<Self, Error> {
+        if !Path::new(file_path).is_file() {
+            return Err(Error::invalid_package(file_path, "The file does not exist"));
+        }
+
+        let file_path_info = FilePathInfo::new(file_path.to_string())?;
+        let ZipFolder {
+            root_folder,
+            extracted_folder,
+        } = get_output_folder(&file_path_info);
+
+        Ok(Self {
+            file_path_info,
+            root_folder,
+            extracted_folder,
+        })
+    }
+
+    /// Whether the extracted folder exists
+    pub fn is_extracted(&self) -> bool {
+        Path::new(&self.extracted_folder).is_dir()
+    }
+
+    /// Extract the packed file, see [`extract_zip`]
+    pub fn extract(&self, overwrite: bool) -> Result<(), Error> {
+        extract_zip(&self.file_path_info, overwrite)
+    }
+
+    /// Extract the packed file into the extracted folder only, the other files of the root folder are kept
+    ///
+    /// Unlike [`Package::extract`] this never removes the root folder, so a hand-edited customXml.json survives a
+    /// missing extracted folder. The manifest is only written when the root folder doesn't have one.
+    pub fn restore_extracted_folder(&self) -> Result<(), Error> {
+        let full_file_path = &self.file_path_info.full_file_path;
+        let file = File::open(full_file_path).map_err(|e| Error::io(full_file_path, e))?;
+        let manifest = extract_zip_from_reader(file, &self.extracted_folder)?;
+
+        let manifest_file_path = self.manifest_file_path();
+        if !Path::new(&manifest_file_path).exists() {
+            write_struct_to_json(&manifest, &manifest_file_path)?;
+        }
+
+        Ok(())
+    }
+
+    /// Load the packed file in memory without extracting it, see [`OoxmlPackage`]
+    pub fn load(&self) -> Result<OoxmlPackage, Error> {
+        OoxmlPackage::open(&self.file_path_info.full_file_path)
+    }
+
+    /// Summarize the extracted folder, see [`summarize`]
+    pub fn summarize(&self) -> Result<SummarizeData, Error> {
+        summarize(self)
+    }
+
+    /// Analyze the custom XMLs in the extracted folder, see [`analyze_custom_xml`]
+    pub fn analyze_custom_xml(&self) -> Result<CustomXmlAnalysis, Error> {
+        analyze_custom_xml(&self.extracted_folder)
+    }
+
+    /// List the bound content controls of the extracted folder, see [`analyze_data_bindings`]
+    pub fn analyze_data_bindings(&self) -> Result<DataBindingAnalysis, Error> {
+        analyze_data_bindings(&self.extracted_folder)
+    }
+
+    /// Validate the structure of the extracted folder, see [`validate_folder`]
+    pub fn validate(&self) -> Result<ValidationReport, Error> {
+        validate_folder(&self.extracted_folder)
+    }
+
+    /// Path to the summary file inside the root folder
+    pub fn summary_file_path(&self) -> String {
+        format!("{}/{}", self.root_folder, SUMMARY_FILE_NAME)
+    }
+
+    /// Path to the analyzed custom XML file inside the root folder
+    pub fn custom_xml_file_path(&self) -> String {
+        format!("{}/{}", self.root_folder, CUSTOM_XML_FILE_NAME)
+    }
+
+    /// Path to the manifest of the original archive entries inside the root folder
+    pub fn manifest_file_path(&self) -> String {
+        format!("{}/{}", self.root_folder, MANIFEST_FILE_NAME)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::ooxml_package::build_test_package;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_restore_extracted_folder_keeps_root_files() {
+        let temp_dir = tempdir().unwrap();
+        let file_path = temp_dir.path().join("sample.docx");
+        build_test_package(&file_path, &[("word/document.xml", "<document/>")])
+            .save(&file_path)
+            .unwrap();
+
+        let package = Package::open(file_path.to_str().unwrap()).unwrap();
+        fs::create_dir_all(&package.root_folder).unwrap();
+        fs::write(package.custom_xml_file_path(), "[]").unwrap();
+
+        package.restore_extracted_folder().unwrap();
+
+        assert!(package.is_extracted());
+        assert!(
+            Path::new(&package.extracted_folder)
+                .join("word/document.xml")
+                .is_file()
+        );
+        assert_eq!(
+            fs::read_to_string(package.custom_xml_file_path()).unwrap(),
+            "[]"
+        );
+        assert!(Path::new(&package.manifest_file_path()).is_file());
+    }
+}
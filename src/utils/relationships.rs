@@ -0,0 +1,201 @@
+use serde::{Deserialize, Serialize};
+
+use crate::utils::xml::XmlElement;
+
+/// The relationship type from the package to its main part, the transitional and strict namespaces only differ in the prefix
+const OFFICE_DOCUMENT_RELATIONSHIP_TYPE_SUFFIX: &str = "/officeDocument";
+
+/// A `<Relationship>` element of a `.rels` part
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Relationship {
+    pub id: String,
+    pub relationship_type: String,
+    /// The target as written in the `.rels` part, see [`resolve_relationship_target`]
+    pub target: String,
+    /// Whether the target is outside the package, e.g. a hyperlink
+    pub is_external: bool,
+}
+
+impl Relationship {
+    /// Whether this relationship points from the package to its main part
+    pub fn is_office_document(&self) -> bool {
+        self.relationship_type
+            .ends_with(OFFICE_DOCUMENT_RELATIONSHIP_TYPE_SUFFIX)
+    }
+}
+
+/// Parse the content of a `.rels` part, relationships missing an `Id` or a `Target` are ignored
+///
+/// The attribute values are unescaped, and commented-out relationships are skipped like any other comment
+pub fn parse_relationships(content: &str) -> Result<Vec<Relationship>, String> {
+    let root = XmlElement::parse(content)?;
+
+    let relationships = root
+        .get_child_elements("Relationship")
+        .filter_map(|element| {
+            Some(Relationship {
+                id: element.get_attribute("Id")?.to_string(),
+                relationship_type: element
+                    .get_attribute("Type")
+                    .unwrap_or_default()
+                    .to_string(),
+                target: element.get_attribute("Target")?.to_string(),
+                is_external: element.get_attribute("TargetMode") == Some("External"),
+            })
+        })
+        .collect();
+
+    Ok(relationships)
+}
+
+/// Whether the part is a `.rels` part, i.e. a `.rels` file inside a `_rels` folder
+pub fn is_rels_part(part_name: &str) -> bool {
+    get_source_part_name(part_name).is_some()
+}
+
+/// The name of the `.rels` part holding the relationships of the given part, an empty name is the package itself
+///
+/// e.g. `word/document.xml` → `word/_rels/document.xml.rels` and `` → `_rels/.rels`
+pub fn get_rels_part_name(source_part_name: &str) -> String {
+    match source_part_name.rsplit_once('/') {
+        Some((folder, file_name)) => format!("{}/_rels/{}.rels", folder, file_name),
+        None => format!("_rels/{}.rels", source_part_name),
+    }
+}
+
+/// The name of the part that a `.rels` part belongs to, the reverse of [`get_rels_part_name`]
+///
+/// `None` if the part is not a `.rels` part
+pub fn get_source_part_name(rels_part_name: &str) -> Option<String> {
+    let (folder, file_name) = match rels_part_name.rsplit_once('/') {
+        Some((folder, file_name)) => (folder, file_name),
+        None => return None,
+    };
+    let source_file_name = file_name.strip_suffix(".rels")?;
+
+    if folder == "_rels" {
+        return Some(source_file_name.to_string());
+    }
+
+    let source_folder = folder.strip_suffix("/_rels")?;
+    Some(format!("{}/{}", source_folder, source_file_name))
+}
+
+/// Resolve the target of a relationship into a part name, relative targets are resolved against the source part's folder
+///
+/// e.g. `../customXml/item1.xml` from `word/document.xml` → `customXml/item1.xml`
+pub fn resolve_relationship_target(source_part_name: &str, target: &str) -> String {
+    // The fragment isn't part of the part name
+    let target = target.split('#').next().unwrap_or_default();
+    let target = percent_decode(target);
+
+    let mut segments: Vec<&str> = Vec::new();
+    if !target.starts_with('/')
+        && let Some((source_folder, _)) = source_part_name.rsplit_once('/')
+    {
+        segments.extend(source_folder.split('/'));
+    }
+
+    for segment in target.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop();
+            }
+            segment => segments.push(segment),
+        }
+    }
+
+    segments.join("/")
+}
+
+/// Decode the `%XX` escapes of a relationship target, invalid escapes are kept as is
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded: Vec<u8> = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        if bytes[index] == b'%'
+            && let Some(hex) = value.get(index + 1..index + 3)
+            && let Ok(byte) = u8::from_str_radix(hex, 16)
+        {
+            decoded.push(byte);
+            index += 3;
+            continue;
+        }
+
+        decoded.push(bytes[index]);
+        index += 1;
+    }
+
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_relationships() {
+        let content = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+    <Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="word/document.xml"/>
+    <Relationship Target="https://example.com" TargetMode="External" Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/hyperlink"/>
+</Relationships>"#;
+
+        let relationships = parse_relationships(content).unwrap();
+
+        assert_eq!(relationships.len(), 2);
+        assert!(relationships[0].is_office_document());
+        assert!(!relationships[0].is_external);
+        assert_eq!(relationships[1].id, "rId2");
+        assert!(relationships[1].is_external);
+
+        // Entities are unescaped and comments are not relationships
+        let relationships = parse_relationships(
+            r#"<Relationships><!-- <Relationship Id="rId1" Type="t" Target="old.xml"/> --><Relationship Id="rId2" Type="t" Target="a&amp;b.xml"/></Relationships>"#,
+        )
+        .unwrap();
+        assert_eq!(relationships.len(), 1);
+        assert_eq!(relationships[0].target, "a&b.xml");
+        assert!(parse_relationships("<Relationships><Relationship>").is_err());
+    }
+
+    #[test]
+    fn test_rels_part_name_round_trip() {
+        for (source_part_name, rels_part_name) in [
+            ("", "_rels/.rels"),
+            ("word/document.xml", "word/_rels/document.xml.rels"),
+            ("customXml/item1.xml", "customXml/_rels/item1.xml.rels"),
+        ] {
+            assert_eq!(get_rels_part_name(source_part_name), rels_part_name);
+            assert_eq!(
+                get_source_part_name(rels_part_name).as_deref(),
+                Some(source_part_name)
+            );
+        }
+
+        assert_eq!(get_source_part_name("word/document.xml"), None);
+        assert_eq!(get_source_part_name("word/document.rels"), None);
+    }
+
+    #[test]
+    fn test_resolve_relationship_target() {
+        assert_eq!(
+            resolve_relationship_target("", "word/document.xml"),
+            "word/document.xml"
+        );
+        assert_eq!(
+            resolve_relationship_target("word/document.xml", "../customXml/item1.xml"),
+            "customXml/item1.xml"
+        );
+        assert_eq!(
+            resolve_relationship_target("word/document.xml", "./media/image%201.png"),
+            "word/media/image 1.png"
+        );
+        assert_eq!(
+            resolve_relationship_target("word/document.xml", "/docProps/app.xml#fragment"),
+            "docProps/app.xml"
+        );
+    }
+}
@@ -1,130 +1,185 @@
-use colored::Colorize;
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use std::{fs::create_dir_all, path::Path};
 
-use crate::utils::ensure_ooxml_exist::ensure_ooxml_exist;
+use crate::utils::content_types::{ContentTypes, PackageType};
+use crate::utils::error::Error;
 use crate::utils::files::get_file_size_in_kb_from_bytes;
 use crate::utils::files::is_file_custom_xml;
 use crate::utils::files::is_image_extension;
 use crate::utils::files::visit_dirs;
 use crate::utils::files::write_struct_to_json;
-use crate::utils::print_utils::print_error_with_panic;
-use crate::utils::types::SUMMARY_FILE_NAME;
-use crate::utils::types::{FileInfo, FilePathInfo, UserPreference};
-use crate::utils::{
-    input_utils::get_path_from_input::get_file_path_from_input, print_utils::print_fn_progress,
-};
-
-#[derive(Serialize, Deserialize)]
-struct MediaInfo {
-    file_count: u32,
-    total_size_in_kb: f64,
-    files: Vec<FileInfo>,
+use crate::utils::ooxml_package::OoxmlPackage;
+use crate::utils::package::Package;
+use crate::utils::types::{FileInfo, FilePathInfo};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MediaInfo {
+    pub file_count: u32,
+    pub total_size_in_kb: f64,
+    pub files: Vec<FileInfo>,
 }
 
-#[derive(Serialize, Deserialize)]
-struct SummarizeData {
-    basic_info: FileInfo,
-    file_count: u32,
-    media_info: MediaInfo,
-    custom_xml_files: Vec<FileInfo>,
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SummarizeData {
+    pub basic_info: FileInfo,
+    /// Detected from `[Content_Types].xml`, a package without it is summarized as a generic package
+    pub package_type: PackageType,
+    /// Name of the main part inside the archive, e.g. `word/document.xml`
+    pub main_part_name: Option<String>,
+    pub file_count: u32,
+    pub media_info: MediaInfo,
+    pub custom_xml_files: Vec<FileInfo>,
+}
+
+/// Summarize the package and write the result to the summary file inside the root folder
+///
+/// The summary file path is [`Package::summary_file_path`]
+pub fn summarize_to_file(package: &Package) -> Result<SummarizeData, Error> {
+    let summarize_data = summarize(package)?;
+    write_struct_to_json(&summarize_data, &package.summary_file_path())?;
+
+    Ok(summarize_data)
 }
 
-/// Summarize the structure of the Word file
-pub fn summarize_wrapper(user_preference: &mut UserPreference) {
-    println!("\n");
-    let fn_name = "Summarize";
-    print_fn_progress(fn_name, "Summarizing...");
+/// Summarize the package straight from the archive, without extracting it, and write the result to the summary file
+///
+/// The root folder is created if needed, the summary file path is [`Package::summary_file_path`]
+pub fn summarize_archive_to_file(package: &Package) -> Result<SummarizeData, Error> {
+    let summarize_data = summarize_archive(&package.load()?);
+
+    create_dir_all(&package.root_folder).map_err(|e| Error::io(&package.root_folder, e))?;
+    write_struct_to_json(&summarize_data, &package.summary_file_path())?;
 
-    let file_path_info = get_file_path_from_input(user_preference);
-    file_path_info.print_info();
+    Ok(summarize_data)
+}
 
-    let summarize_result = summarize(&file_path_info);
-    if summarize_result.is_err() {
-        print_error_with_panic(&format!(
-            "Failed to summarize: {}",
-            summarize_result.err().unwrap()
+/// Recursively traverse the extracted folder and count the number of files, images, custom XMLs, etc
+///
+/// The package must be extracted first
+pub fn summarize(package: &Package) -> Result<SummarizeData, Error> {
+    if !package.is_extracted() {
+        return Err(Error::invalid_package(
+            &package.extracted_folder,
+            "The extracted folder does not exist",
         ));
     }
 
-    let (summarize_data, root_folder) = summarize_result.unwrap();
+    let mut files: Vec<FileInfo> = Vec::new();
+    let mut file_errors: Vec<Error> = Vec::new();
 
-    let output_path = format!("{}/{}", root_folder, SUMMARY_FILE_NAME);
+    let output_path = Path::new(&package.extracted_folder);
+    let visit_result = visit_dirs(output_path, &mut |entry| {
+        let path = entry.path();
+        match FilePathInfo::new(path.to_string_lossy().to_string()) {
+            Ok(FilePathInfo {
+                file_name_with_extension,
+                full_file_path,
+                file_size,
+                ..
+            }) => files.push(FileInfo {
+                file_name_with_extension,
+                full_file_path,
+                file_size_in_kb: get_file_size_in_kb_from_bytes(file_size),
+            }),
+            Err(e) => file_errors.push(e),
+        }
+    });
 
-    let write_result = write_struct_to_json(&summarize_data, &output_path);
-    if write_result.is_err() {
-        print_error_with_panic(&format!(
-            "Failed to write the summarize result to the file: {}",
-            write_result.err().unwrap()
-        ));
+    if let Err(e) = visit_result {
+        return Err(Error::io(output_path, e));
+    }
+    if let Some(error) = file_errors.into_iter().next() {
+        return Err(error);
     }
 
-    println!("Summary file: {}", output_path);
-    println!("{}", "Summarization completed successfully!".green());
+    let file_path_info = &package.file_path_info;
+    let basic_info = FileInfo {
+        file_name_with_extension: file_path_info.file_name_with_extension.clone(),
+        full_file_path: file_path_info.full_file_path.clone(),
+        file_size_in_kb: get_file_size_in_kb_from_bytes(file_path_info.file_size),
+    };
+
+    let content_types = ContentTypes::read_from_folder(&package.extracted_folder).ok();
+
+    Ok(summarize_files(basic_info, content_types, files))
 }
 
-/// Recursively traverse the extracted folder and count the number of files, images, custom XMLs, etc
-fn summarize(file_path_info: &FilePathInfo) -> Result<(SummarizeData, String), &'static str> {
-    let (extracted_folder, root_folder) = ensure_ooxml_exist(file_path_info)?;
+/// Count the number of parts, images, custom XMLs, etc straight from the in-memory package
+///
+/// The `full_file_path` of each part is its name inside the archive
+pub fn summarize_archive(ooxml_package: &OoxmlPackage) -> SummarizeData {
+    let files = ooxml_package
+        .parts()
+        .iter()
+        .map(|part| FileInfo {
+            file_name_with_extension: part
+                .name
+                .rsplit('/')
+                .next()
+                .unwrap_or(&part.name)
+                .to_string(),
+            full_file_path: part.name.clone(),
+            file_size_in_kb: get_file_size_in_kb_from_bytes(part.data.len() as u64),
+        })
+        .collect();
+
+    let basic_info = FileInfo {
+        file_name_with_extension: ooxml_package
+            .path
+            .file_name()
+            .map(|file_name| file_name.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        full_file_path: ooxml_package.path.to_string_lossy().to_string(),
+        file_size_in_kb: get_file_size_in_kb_from_bytes(ooxml_package.size),
+    };
+
+    let content_types = ContentTypes::read_from_archive(ooxml_package).ok();
 
-    // * Continue with the summarization
-    let mut file_count = 0;
+    summarize_files(basic_info, content_types, files)
+}
+
+/// Sort the files of a package into media and custom XMLs
+fn summarize_files(
+    basic_info: FileInfo,
+    content_types: Option<ContentTypes>,
+    files: Vec<FileInfo>,
+) -> SummarizeData {
     let mut media_info = MediaInfo {
         file_count: 0,
         total_size_in_kb: 0.0,
         files: Vec::new(),
     };
     let mut custom_xml_files: Vec<FileInfo> = Vec::new();
+    let file_count = files.len() as u32;
 
-    let output_path = Path::new(&extracted_folder);
-    let visit_result = visit_dirs(output_path, &mut |entry| {
-        let path = entry.path();
-        let FilePathInfo {
-            file_name_with_extension,
-            file_size,
-            file_extension,
-            full_file_path,
-            ..
-        } = FilePathInfo::new(path.to_string_lossy().to_string());
-
-        let file_size_in_kb = get_file_size_in_kb_from_bytes(file_size);
-        file_count += 1;
+    for file_info in files {
+        let file_extension = Path::new(&file_info.file_name_with_extension)
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_string());
 
         if let Some(file_extension) = file_extension {
-            if is_file_custom_xml(file_name_with_extension.as_str()) {
-                custom_xml_files.push(FileInfo {
-                    file_name_with_extension,
-                    full_file_path,
-                    file_size_in_kb,
-                });
+            if is_file_custom_xml(file_info.file_name_with_extension.as_str()) {
+                custom_xml_files.push(file_info);
             } else if is_image_extension(&file_extension) {
                 media_info.file_count += 1;
-                media_info.total_size_in_kb += file_size_in_kb;
-                media_info.files.push(FileInfo {
-                    file_name_with_extension,
-                    full_file_path,
-                    file_size_in_kb,
-                });
+                media_info.total_size_in_kb += file_info.file_size_in_kb;
+                media_info.files.push(file_info);
             }
         }
-    });
-
-    if visit_result.is_err() {
-        return Err("Failed to visit the directory");
     }
 
-    Ok((
-        SummarizeData {
-            basic_info: FileInfo {
-                file_name_with_extension: file_path_info.file_name_with_extension.clone(),
-                full_file_path: file_path_info.full_file_path.clone(),
-                file_size_in_kb: get_file_size_in_kb_from_bytes(file_path_info.file_size),
-            },
-            file_count,
-            media_info,
-            custom_xml_files,
-        },
-        root_folder,
-    ))
+    SummarizeData {
+        basic_info,
+        package_type: content_types
+            .as_ref()
+            .map(ContentTypes::package_type)
+            .unwrap_or(PackageType::Opc),
+        main_part_name: content_types
+            .as_ref()
+            .and_then(ContentTypes::main_part_name)
+            .map(str::to_string),
+        file_count,
+        media_info,
+        custom_xml_files,
+    }
 }
-
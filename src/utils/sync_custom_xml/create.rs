@@ -0,0 +1,335 @@
+use quick_xml::escape::escape;
+use std::{fs, path::Path};
+
+use crate::utils::{
+    analyze_custom_xml::main::CustomXmlFile,
+    content_types::ContentTypes,
+    error::Error,
+    files::is_file_custom_xml,
+    relationships::{Relationship, get_rels_part_name, parse_relationships},
+    zip_utils::main::CONTENT_TYPES_PART_NAME,
+};
+
+const XML_DECLARATION: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="no"?>"#;
+const CUSTOM_XML_RELATIONSHIP_TYPE: &str =
+    "http://schemas.openxmlformats.org/officeDocument/2006/relationships/customXml";
+const CUSTOM_XML_PROPS_RELATIONSHIP_TYPE: &str =
+    "http://schemas.openxmlformats.org/officeDocument/2006/relationships/customXmlProps";
+const CUSTOM_XML_PROPS_CONTENT_TYPE: &str =
+    "application/vnd.openxmlformats-officedocument.customXmlProperties+xml";
+const RELATIONSHIPS_NAMESPACE: &str =
+    "http://schemas.openxmlformats.org/package/2006/relationships";
+
+/// Create a custom XML part that isn't in the extracted folder yet, along with everything that links it to the package
+///
+/// - `itemN.xml` with the content of the entry
+/// - `itemPropsN.xml` with the datastore item ID and schema references of the entry, or a fresh item ID
+/// - `_rels/itemN.xml.rels` pointing from the item to its props
+/// - the content type override of the props in `[Content_Types].xml`
+/// - the relationship from the main part to the item
+///
+/// The existing parts are only appended to, and nothing is written if any of them can't be updated.
+/// Returns the datastore item ID, e.g. `{32D1BF7B-7F44-494E-A02A-0CAFF277A406}`
+pub fn create_custom_xml_part(
+    extracted_folder: &str,
+    custom_xml_folder_name: &str,
+    custom_xml_file: &CustomXmlFile,
+) -> Result<String, Error> {
+    let extracted_folder_path = Path::new(extracted_folder);
+    let file_name = &custom_xml_file.file_info.file_name_with_extension;
+    if !is_file_custom_xml(file_name) {
+        return Err(Error::custom_xml_parse(
+            &custom_xml_file.file_info.full_file_path,
+            "A new custom XML file must be named itemN.xml",
+        ));
+    }
+
+    let item_part_name = format!("{}/{}", custom_xml_folder_name, file_name);
+    let props_file_name = file_name.replacen("item", "itemProps", 1);
+    let props_part_name = format!("{}/{}", custom_xml_folder_name, props_file_name);
+    if extracted_folder_path.join(&props_part_name).exists() {
+        return Err(Error::invalid_package(
+            extracted_folder_path.join(&props_part_name),
+            format!(
+                "The item props of the new custom XML {} already exist",
+                file_name
+            ),
+        ));
+    }
+
+    // * Build every new or updated part before writing any of them
+    let item_path = extracted_folder_path.join(&item_part_name);
+    let item_content = custom_xml_file
+        .custom_xml_info
+        .to_xml_element()
+        .map_err(|e| Error::custom_xml_parse(&item_path, e))?
+        .to_xml_string();
+
+    // Keeping the item ID of the entry lets the next sync match the part even if Word renumbers it
+    let item_props = custom_xml_file.item_props.as_ref();
+    let item_id = match item_props {
+        Some(item_props) => item_props.item_id.clone(),
+        None => format!("{{{}}}", uuid::Uuid::new_v4().to_string().to_uppercase()),
+    };
+    let schema_refs: String = item_props
+        .map(|item_props| item_props.schema_refs.as_slice())
+        .unwrap_or_default()
+        .iter()
+        .map(|uri| format!("<ds:schemaRef ds:uri=\"{}\"/>", escape(uri)))
+        .collect();
+    let schema_refs = if schema_refs.is_empty() {
+        "<ds:schemaRefs/>".to_string()
+    } else {
+        format!("<ds:schemaRefs>{}</ds:schemaRefs>", schema_refs)
+    };
+    let props_content = format!(
+        "{}\n<ds:datastoreItem ds:itemID=\"{}\" xmlns:ds=\"http://schemas.openxmlformats.org/officeDocument/2006/customXml\">{}</ds:datastoreItem>",
+        XML_DECLARATION,
+        escape(&item_id),
+        schema_refs
+    );
+    let item_rels_content = format!(
+        "{}\n<Relationships xmlns=\"{}\"><Relationship Id=\"rId1\" Type=\"{}\" Target=\"{}\"/></Relationships>",
+        XML_DECLARATION,
+        RELATIONSHIPS_NAMESPACE,
+        CUSTOM_XML_PROPS_RELATIONSHIP_TYPE,
+        props_file_name
+    );
+
+    let content_types_path = extracted_folder_path.join(CONTENT_TYPES_PART_NAME);
+    let content_types_content =
+        fs::read_to_string(&content_types_path).map_err(|e| Error::io(&content_types_path, e))?;
+    let content_types = ContentTypes::parse(&content_types_content)
+        .map_err(|e| Error::invalid_package(&content_types_path, e))?;
+    let mut new_content_types = format!(
+        "<Override PartName=\"/{}\" ContentType=\"{}\"/>",
+        props_part_name, CUSTOM_XML_PROPS_CONTENT_TYPE
+    );
+    if content_types.get_content_type(&item_part_name).is_none() {
+        new_content_types.insert_str(
+            0,
+            "<Default Extension=\"xml\" ContentType=\"application/xml\"/>",
+        );
+    }
+    let content_types_content =
+        insert_before_closing_tag(&content_types_content, "</Types>", &new_content_types)
+            .ok_or_else(|| {
+                Error::invalid_package(&content_types_path, "Missing the </Types> end tag")
+            })?;
+
+    let main_part_name = content_types.main_part_name().ok_or_else(|| {
+        Error::invalid_package(
+            extracted_folder_path,
+            "The package doesn't have a main part to link the custom XML to",
+        )
+    })?;
+    let main_rels_path = extracted_folder_path.join(get_rels_part_name(main_part_name));
+    let main_rels_content = if main_rels_path.is_file() {
+        fs::read_to_string(&main_rels_path).map_err(|e| Error::io(&main_rels_path, e))?
+    } else {
+        format!(
+            "{}\n<Relationships xmlns=\"{}\"></Relationships>",
+            XML_DECLARATION, RELATIONSHIPS_NAMESPACE
+        )
+    };
+    let relationship = format!(
+        "<Relationship Id=\"{}\" Type=\"{}\" Target=\"{}\"/>",
+        get_new_relationship_id(
+            &parse_relationships(&main_rels_content)
+                .map_err(|e| Error::invalid_package(&main_rels_path, e))?
+        ),
+        CUSTOM_XML_RELATIONSHIP_TYPE,
+        get_relative_target(main_part_name, &item_part_name)
+    );
+    let main_rels_content =
+        insert_before_closing_tag(&main_rels_content, "</Relationships>", &relationship)
+            .ok_or_else(|| {
+                Error::invalid_package(&main_rels_path, "Missing the </Relationships> end tag")
+            })?;
+
+    // * Write the parts
+    let custom_xml_rels_folder = extracted_folder_path
+        .join(custom_xml_folder_name)
+        .join("_rels");
+    fs::create_dir_all(&custom_xml_rels_folder)
+        .map_err(|e| Error::io(&custom_xml_rels_folder, e))?;
+    if let Some(main_rels_folder) = main_rels_path.parent() {
+        fs::create_dir_all(main_rels_folder).map_err(|e| Error::io(main_rels_folder, e))?;
+    }
+
+    for (path, content) in [
+        (item_path, format!("{}\n{}", XML_DECLARATION, item_content)),
+        (extracted_folder_path.join(&props_part_name), props_content),
+        (
+            extracted_folder_path.join(get_rels_part_name(&item_part_name)),
+            item_rels_content,
+        ),
+        (content_types_path, content_types_content),
+        (main_rels_path, main_rels_content),
+    ] {
+        fs::write(&path, content).map_err(|e| Error::io(&path, e))?;
+    }
+
+    Ok(item_id)
+}
+
+/// Insert the elements after the last child of the root element, i.e. before the whitespace and the closing tag
+///
+/// `None` if there's no such tag
+fn insert_before_closing_tag(content: &str, closing_tag: &str, elements: &str) -> Option<String> {
+    let position = content[..content.rfind(closing_tag)?].trim_end().len();
+    let mut updated_content = content.to_string();
+    updated_content.insert_str(position, elements);
+    Some(updated_content)
+}
+
+/// The first `rIdN` that isn't used by the relationships yet
+fn get_new_relationship_id(relationships: &[Relationship]) -> String {
+    (1..)
+        .map(|index| format!("rId{}", index))
+        .find(|id| {
+            !relationships
+                .iter()
+                .any(|relationship| relationship.id == *id)
+        })
+        .unwrap()
+}
+
+/// The target of a relationship from the source part to the target part, relative to the source part's folder
+///
+/// e.g. `customXml/item1.xml` from `word/document.xml` → `../customXml/item1.xml`
+fn get_relative_target(source_part_name: &str, target_part_name: &str) -> String {
+    let depth = source_part_name.matches('/').count();
+    format!("{}{}", "../".repeat(depth), target_part_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{
+        analyze_custom_xml::{
+            item_props::{CustomXmlProps, parse_custom_xml_props},
+            main::parse_custom_xml_content,
+        },
+        relationships::resolve_relationship_target,
+        types::FileInfo,
+        validate::main::validate_folder,
+    };
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_create_custom_xml_part() {
+        let temp_dir = tempdir().unwrap();
+        let extracted_folder = temp_dir.path();
+        fs::create_dir_all(extracted_folder.join("_rels")).unwrap();
+        fs::create_dir_all(extracted_folder.join("word/_rels")).unwrap();
+        fs::write(
+            extracted_folder.join(CONTENT_TYPES_PART_NAME),
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Override PartName="/word/document.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml"/></Types>"#,
+        )
+        .unwrap();
+        fs::write(
+            extracted_folder.join("_rels/.rels"),
+            r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="word/document.xml"/></Relationships>"#,
+        )
+        .unwrap();
+        fs::write(extracted_folder.join("word/document.xml"), "<w:document/>").unwrap();
+        fs::write(
+            extracted_folder.join("word/_rels/document.xml.rels"),
+            r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/styles" Target="styles.xml"/></Relationships>"#,
+        )
+        .unwrap();
+        fs::write(extracted_folder.join("word/styles.xml"), "<w:styles/>").unwrap();
+
+        let custom_xml_file = CustomXmlFile {
+            file_info: FileInfo {
+                file_name_with_extension: "item1.xml".to_string(),
+                full_file_path: "customXml/item1.xml".to_string(),
+                file_size_in_kb: 0.0,
+            },
+            item_props: None,
+            custom_xml_info: parse_custom_xml_content(r#"<root>{"key":"value"}</root>"#).unwrap(),
+        };
+
+        let extracted_folder_str = extracted_folder.to_str().unwrap();
+        let item_id =
+            create_custom_xml_part(extracted_folder_str, "customXml", &custom_xml_file).unwrap();
+
+        assert_eq!(item_id.len(), 38);
+        assert!(
+            fs::read_to_string(extracted_folder.join("customXml/item1.xml"))
+                .unwrap()
+                .ends_with(r#"<root>{"key":"value"}</root>"#)
+        );
+        assert!(
+            fs::read_to_string(extracted_folder.join("customXml/itemProps1.xml"))
+                .unwrap()
+                .contains(&item_id)
+        );
+
+        let main_rels =
+            fs::read_to_string(extracted_folder.join("word/_rels/document.xml.rels")).unwrap();
+        let relationship = parse_relationships(&main_rels)
+            .unwrap()
+            .into_iter()
+            .find(|relationship| relationship.relationship_type == CUSTOM_XML_RELATIONSHIP_TYPE)
+            .unwrap();
+        assert_eq!(relationship.id, "rId2");
+        assert_eq!(
+            resolve_relationship_target("word/document.xml", &relationship.target),
+            "customXml/item1.xml"
+        );
+
+        // The new parts are linked, typed and reachable
+        let report = validate_folder(extracted_folder_str).unwrap();
+        assert!(report.is_valid(), "{:?}", report.issues);
+
+        // The item ID and schema references of the entry are kept
+        let custom_xml_file_with_props = CustomXmlFile {
+            file_info: FileInfo {
+                file_name_with_extension: "item2.xml".to_string(),
+                full_file_path: "customXml/item2.xml".to_string(),
+                file_size_in_kb: 0.0,
+            },
+            item_props: Some(CustomXmlProps {
+                item_id: "{A}".to_string(),
+                schema_refs: vec!["urn:schema".to_string()],
+            }),
+            custom_xml_info: parse_custom_xml_content("<root/>").unwrap(),
+        };
+        assert_eq!(
+            create_custom_xml_part(
+                extracted_folder_str,
+                "customXml",
+                &custom_xml_file_with_props
+            )
+            .unwrap(),
+            "{A}"
+        );
+        assert_eq!(
+            parse_custom_xml_props(
+                &fs::read_to_string(extracted_folder.join("customXml/itemProps2.xml")).unwrap()
+            ),
+            Ok(custom_xml_file_with_props.item_props.unwrap())
+        );
+
+        // The item props can't be created twice
+        fs::remove_file(extracted_folder.join("customXml/item1.xml")).unwrap();
+        assert!(
+            create_custom_xml_part(extracted_folder_str, "customXml", &custom_xml_file).is_err()
+        );
+    }
+
+    #[test]
+    fn test_get_relative_target() {
+        assert_eq!(
+            get_relative_target("word/document.xml", "customXml/item1.xml"),
+            "../customXml/item1.xml"
+        );
+        assert_eq!(
+            get_relative_target("document.xml", "customXml/item1.xml"),
+            "customXml/item1.xml"
+        );
+    }
+}
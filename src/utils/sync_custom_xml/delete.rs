@@ -0,0 +1,230 @@
+use std::{fs, path::Path};
+
+use crate::utils::{
+    analyze_custom_xml::item_props::get_custom_xml_props_part_name,
+    error::Error,
+    files::get_part_names_in_folder,
+    relationships::{
+        get_rels_part_name, get_source_part_name, is_rels_part, resolve_relationship_target,
+    },
+    xml::{XmlElement, XmlNode},
+    zip_utils::main::CONTENT_TYPES_PART_NAME,
+};
+
+/// Remove a custom XML part from the extracted folder along with everything that links it to the package
+///
+/// - the item and its item props
+/// - `_rels/itemN.xml.rels`
+/// - the content type overrides of the removed parts
+/// - the relationships pointing at the item, from any `.rels` part
+///
+/// The item part name is relative to the extracted folder, e.g. `customXml/item1.xml`. The other parts only lose the
+/// removed elements, and nothing is written or removed if any of them can't be updated.
+pub fn delete_custom_xml_part(extracted_folder: &str, item_part_name: &str) -> Result<(), Error> {
+    let extracted_folder_path = Path::new(extracted_folder);
+
+    // * Find the item props through the item's relationships
+    let item_rels_part_name = get_rels_part_name(item_part_name);
+    let props_part_name = get_custom_xml_props_part_name(item_part_name, |part_name| {
+        fs::read_to_string(extracted_folder_path.join(part_name)).ok()
+    });
+    let removed_part_names = [item_part_name, props_part_name.as_str()];
+
+    // * Drop the content type overrides and the relationships of the removed parts
+    let mut updated_parts: Vec<(String, String)> = Vec::new();
+
+    let content_types_path = extracted_folder_path.join(CONTENT_TYPES_PART_NAME);
+    let content_types_content =
+        fs::read_to_string(&content_types_path).map_err(|e| Error::io(&content_types_path, e))?;
+    let updated_content = remove_child_elements(&content_types_content, |element| {
+        element.tag == "Override"
+            && element
+                .attributes
+                .get("PartName")
+                .is_some_and(|part_name| is_same_part(part_name, &removed_part_names))
+    })
+    .map_err(|e| Error::invalid_package(&content_types_path, e))?;
+    if updated_content != content_types_content {
+        updated_parts.push((CONTENT_TYPES_PART_NAME.to_string(), updated_content));
+    }
+
+    for rels_part_name in get_part_names_in_folder(extracted_folder_path)? {
+        if !is_rels_part(&rels_part_name) || rels_part_name == item_rels_part_name {
+            continue;
+        }
+        let Some(source_part_name) = get_source_part_name(&rels_part_name) else {
+            continue;
+        };
+
+        let rels_path = extracted_folder_path.join(&rels_part_name);
+        let rels_content = fs::read_to_string(&rels_path).map_err(|e| Error::io(&rels_path, e))?;
+        let updated_content = remove_child_elements(&rels_content, |element| {
+            let is_external = element
+                .attributes
+                .get("TargetMode")
+                .is_some_and(|target_mode| target_mode == "External");
+            element.tag == "Relationship"
+                && !is_external
+                && element.attributes.get("Target").is_some_and(|target| {
+                    is_same_part(
+                        &resolve_relationship_target(&source_part_name, target),
+                        &removed_part_names,
+                    )
+                })
+        })
+        .map_err(|e| Error::invalid_package(&rels_path, e))?;
+        if updated_content != rels_content {
+            updated_parts.push((rels_part_name, updated_content));
+        }
+    }
+
+    // * Write the updated parts, then remove the custom XML ones
+    for (part_name, content) in updated_parts {
+        let path = extracted_folder_path.join(part_name);
+        fs::write(&path, content).map_err(|e| Error::io(&path, e))?;
+    }
+    for part_name in [item_part_name, &props_part_name, &item_rels_part_name] {
+        let path = extracted_folder_path.join(part_name);
+        if path.is_file() {
+            fs::remove_file(&path).map_err(|e| Error::io(&path, e))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Whether the part name (with or without the leading `/`) is one of the given part names, ignoring the case
+fn is_same_part(part_name: &str, part_names: &[&str]) -> bool {
+    let part_name = part_name.trim_start_matches('/');
+    part_names
+        .iter()
+        .any(|other_part_name| other_part_name.eq_ignore_ascii_case(part_name))
+}
+
+/// Remove the children of the root element matching the predicate, along with the whitespace before them
+fn remove_child_elements(
+    content: &str,
+    should_remove: impl Fn(&XmlElement) -> bool,
+) -> Result<String, String> {
+    let (root, span) = XmlElement::parse_with_spans(content)?;
+
+    let mut updated_content = content.to_string();
+    for (child, child_span) in root.children.iter().zip(&span.children).rev() {
+        if let XmlNode::Element(element) = child
+            && should_remove(element)
+        {
+            let start = content[..child_span.range.start].trim_end().len();
+            updated_content.replace_range(start..child_span.range.end, "");
+        }
+    }
+
+    Ok(updated_content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{
+        analyze_custom_xml::main::{CustomXmlFile, parse_custom_xml_content},
+        sync_custom_xml::create::create_custom_xml_part,
+        types::FileInfo,
+        validate::main::validate_folder,
+    };
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_delete_custom_xml_part() {
+        let temp_dir = tempdir().unwrap();
+        let extracted_folder = temp_dir.path();
+        let content_types = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">
+    <Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>
+    <Default Extension="xml" ContentType="application/xml"/>
+    <Override PartName="/word/document.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml"/>
+</Types>"#;
+        let document_rels = r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"></Relationships>"#;
+        fs::create_dir_all(extracted_folder.join("_rels")).unwrap();
+        fs::create_dir_all(extracted_folder.join("word/_rels")).unwrap();
+        fs::write(
+            extracted_folder.join(CONTENT_TYPES_PART_NAME),
+            content_types,
+        )
+        .unwrap();
+        fs::write(
+            extracted_folder.join("_rels/.rels"),
+            r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="word/document.xml"/></Relationships>"#,
+        )
+        .unwrap();
+        fs::write(extracted_folder.join("word/document.xml"), "<w:document/>").unwrap();
+        fs::write(
+            extracted_folder.join("word/_rels/document.xml.rels"),
+            document_rels,
+        )
+        .unwrap();
+
+        let extracted_folder_str = extracted_folder.to_str().unwrap();
+        let custom_xml_file = CustomXmlFile {
+            file_info: FileInfo {
+                file_name_with_extension: "item1.xml".to_string(),
+                full_file_path: "customXml/item1.xml".to_string(),
+                file_size_in_kb: 0.0,
+            },
+            item_props: None,
+            custom_xml_info: parse_custom_xml_content("<root/>").unwrap(),
+        };
+        create_custom_xml_part(extracted_folder_str, "customXml", &custom_xml_file).unwrap();
+
+        delete_custom_xml_part(extracted_folder_str, "customXml/item1.xml").unwrap();
+
+        // Everything the part added is gone
+        assert_eq!(
+            fs::read_to_string(extracted_folder.join(CONTENT_TYPES_PART_NAME)).unwrap(),
+            content_types
+        );
+        assert_eq!(
+            fs::read_to_string(extracted_folder.join("word/_rels/document.xml.rels")).unwrap(),
+            document_rels
+        );
+        for part_name in [
+            "customXml/item1.xml",
+            "customXml/itemProps1.xml",
+            "customXml/_rels/item1.xml.rels",
+        ] {
+            assert!(!extracted_folder.join(part_name).exists());
+        }
+        let report = validate_folder(extracted_folder_str).unwrap();
+        assert!(report.is_valid(), "{:?}", report.issues);
+    }
+
+    #[test]
+    fn test_delete_custom_xml_part_err_on_malformed_content_types() {
+        let temp_dir = tempdir().unwrap();
+        fs::create_dir_all(temp_dir.path().join("customXml")).unwrap();
+        fs::write(temp_dir.path().join("customXml/item1.xml"), "<root/>").unwrap();
+        fs::write(
+            temp_dir.path().join(CONTENT_TYPES_PART_NAME),
+            "<Types><Override>",
+        )
+        .unwrap();
+
+        let result =
+            delete_custom_xml_part(temp_dir.path().to_str().unwrap(), "customXml/item1.xml");
+
+        // A broken package part, not an unsupported custom XML
+        assert!(matches!(result, Err(Error::InvalidPackage { .. })));
+        assert!(temp_dir.path().join("customXml/item1.xml").is_file());
+    }
+
+    #[test]
+    fn test_remove_child_elements() {
+        let content =
+            "<Types>\n  <Default Extension=\"xml\"/>\n  <Override PartName=\"/a.xml\"/>\n</Types>";
+
+        let updated_content = remove_child_elements(content, |element| element.tag == "Override");
+
+        assert_eq!(
+            updated_content.unwrap(),
+            "<Types>\n  <Default Extension=\"xml\"/>\n</Types>"
+        );
+    }
+}
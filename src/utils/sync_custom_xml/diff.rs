@@ -0,0 +1,233 @@
+use serde_json::{Map, Value};
+use std::{collections::BTreeSet, fmt};
+
+use crate::utils::analyze_custom_xml::main::{CustomXmlElement, CustomXmlInfo};
+
+/// A difference between the custom XML info on disk and the one in customXml.json
+///
+/// The path points into [`CustomXmlInfo`], e.g. `json_content.items[0].name` or `attributes.id`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JsonChange {
+    Added {
+        path: String,
+        value: Value,
+    },
+    Removed {
+        path: String,
+        value: Value,
+    },
+    Changed {
+        path: String,
+        old_value: Value,
+        new_value: Value,
+    },
+}
+
+impl fmt::Display for JsonChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JsonChange::Added { path, value } => write!(f, "+ {}: {}", path, value),
+            JsonChange::Removed { path, value } => write!(f, "- {}: {}", path, value),
+            JsonChange::Changed {
+                path,
+                old_value,
+                new_value,
+            } => write!(f, "~ {}: {} → {}", path, old_value, new_value),
+        }
+    }
+}
+
+/// The changes an update would make to a custom XML file
+#[derive(Debug, Clone)]
+pub struct SyncDiff {
+    pub file_name: String,
+    pub changes: Vec<JsonChange>,
+}
+
+/// The structural differences from the current info to the expected one, nested objects and arrays are compared item
+/// by item so only the values that changed are listed
+pub fn diff_custom_xml_info(current: &CustomXmlInfo, expected: &CustomXmlInfo) -> Vec<JsonChange> {
+    let mut changes = Vec::new();
+
+    diff_json(
+        "tag",
+        &Value::from(current.tag.as_str()),
+        &Value::from(expected.tag.as_str()),
+        &mut changes,
+    );
+    diff_json(
+        "format",
+        &serde_json::to_value(current.format).unwrap(),
+        &serde_json::to_value(expected.format).unwrap(),
+        &mut changes,
+    );
+    // Missing attributes are the same as no attributes
+    let no_attributes = Value::Object(Map::new());
+    diff_json(
+        "attributes",
+        current.attributes.as_ref().unwrap_or(&no_attributes),
+        expected.attributes.as_ref().unwrap_or(&no_attributes),
+        &mut changes,
+    );
+    diff_json(
+        "json_content",
+        &current.json_content,
+        &expected.json_content,
+        &mut changes,
+    );
+
+    let element_paths: BTreeSet<&str> = current
+        .elements
+        .iter()
+        .chain(&expected.elements)
+        .map(|element| element.path.as_str())
+        .collect();
+    for element_path in element_paths {
+        let path = format!("elements[{}]", Value::from(element_path));
+        let find_element = |elements: &[CustomXmlElement]| {
+            elements
+                .iter()
+                .find(|element| element.path == element_path)
+                .map(|element| serde_json::to_value(element).unwrap())
+        };
+        match (
+            find_element(&current.elements),
+            find_element(&expected.elements),
+        ) {
+            (Some(old_element), Some(new_element)) => {
+                diff_json(&path, &old_element, &new_element, &mut changes)
+            }
+            (Some(old_element), None) => changes.push(JsonChange::Removed {
+                path,
+                value: old_element,
+            }),
+            (None, Some(new_element)) => changes.push(JsonChange::Added {
+                path,
+                value: new_element,
+            }),
+            (None, None) => {}
+        }
+    }
+
+    changes
+}
+
+fn diff_json(path: &str, old_value: &Value, new_value: &Value, changes: &mut Vec<JsonChange>) {
+    match (old_value, new_value) {
+        (Value::Object(old_object), Value::Object(new_object)) => {
+            for (key, old_item) in old_object {
+                let item_path = get_key_path(path, key);
+                match new_object.get(key) {
+                    Some(new_item) => diff_json(&item_path, old_item, new_item, changes),
+                    None => changes.push(JsonChange::Removed {
+                        path: item_path,
+                        value: old_item.clone(),
+                    }),
+                }
+            }
+            for (key, new_item) in new_object {
+                if !old_object.contains_key(key) {
+                    changes.push(JsonChange::Added {
+                        path: get_key_path(path, key),
+                        value: new_item.clone(),
+                    });
+                }
+            }
+        }
+        (Value::Array(old_array), Value::Array(new_array)) => {
+            for index in 0..old_array.len().max(new_array.len()) {
+                let item_path = format!("{}[{}]", path, index);
+                match (old_array.get(index), new_array.get(index)) {
+                    (Some(old_item), Some(new_item)) => {
+                        diff_json(&item_path, old_item, new_item, changes)
+                    }
+                    (Some(old_item), None) => changes.push(JsonChange::Removed {
+                        path: item_path,
+                        value: old_item.clone(),
+                    }),
+                    (None, Some(new_item)) => changes.push(JsonChange::Added {
+                        path: item_path,
+                        value: new_item.clone(),
+                    }),
+                    (None, None) => {}
+                }
+            }
+        }
+        _ if old_value != new_value => changes.push(JsonChange::Changed {
+            path: path.to_string(),
+            old_value: old_value.clone(),
+            new_value: new_value.clone(),
+        }),
+        _ => {}
+    }
+}
+
+/// `parent.key`, or `parent["some key"]` when the key isn't a plain identifier
+fn get_key_path(parent_path: &str, key: &str) -> String {
+    let is_identifier = !key.is_empty()
+        && !key.starts_with(|c: char| c.is_ascii_digit())
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if is_identifier {
+        format!("{}.{}", parent_path, key)
+    } else {
+        format!("{}[{}]", parent_path, Value::from(key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::analyze_custom_xml::main::parse_custom_xml_content;
+    use serde_json::json;
+
+    #[test]
+    fn test_diff_custom_xml_info() {
+        let current = parse_custom_xml_content(
+            r#"<root id="1" xmlns:ns0="urn:x">{"name":"a","items":[1,2],"nested":{"flag":true}}</root>"#,
+        )
+        .unwrap();
+        let mut expected = parse_custom_xml_content(&format!(
+            "<root id=\"2\" xmlns:ns0=\"urn:x\">{}</root>",
+            r#"{"items":[1,3,4],"nested":{"flag":true},"new key":null}"#
+        ))
+        .unwrap();
+
+        assert_eq!(
+            diff_custom_xml_info(&current, &expected)
+                .iter()
+                .map(|change| change.to_string())
+                .collect::<Vec<_>>(),
+            vec![
+                "~ attributes.id: \"1\" → \"2\"",
+                // The keys in the order of the document, the added ones last
+                "- json_content.name: \"a\"",
+                "~ json_content.items[1]: 2 → 3",
+                "+ json_content.items[2]: 4",
+                "+ json_content[\"new key\"]: null",
+            ]
+        );
+
+        expected.json_content = current.json_content.clone();
+        expected.attributes = current.attributes.clone();
+        assert!(diff_custom_xml_info(&current, &expected).is_empty());
+    }
+
+    #[test]
+    fn test_diff_custom_xml_info_elements() {
+        let current = parse_custom_xml_content("<root><a>{\"x\":1}</a><b>[]</b></root>").unwrap();
+        let mut expected =
+            parse_custom_xml_content("<root><a>{\"x\":2}</a><b>[]</b></root>").unwrap();
+        expected.elements[1].attributes = Some(json!({ "id": "1" }));
+
+        assert_eq!(
+            diff_custom_xml_info(&current, &expected)
+                .iter()
+                .map(|change| change.to_string())
+                .collect::<Vec<_>>(),
+            vec![
+                "~ elements[\"/root/a\"].json_content.x: 1 → 2",
+                "~ elements[\"/root/b\"].attributes: null → {\"id\":\"1\"}",
+            ]
+        );
+    }
+}
@@ -1,115 +1,314 @@
 use colored::Colorize;
+use quick_xml::escape::{escape, partial_escape};
+use std::collections::{BTreeMap, HashSet};
 use std::fs;
 use std::path::Path;
 
 use crate::utils::{
-    analyze_custom_xml::main::{CustomXmlFile, CustomXmlInfo, parse_custom_xml_content_for_tag},
-    files::read_struct_from_json,
-    input_utils::get_path_from_input::get_extracted_root_folder_path,
-    print_utils::{get_error_message, print_error_with_panic, print_fn_progress},
-    types::{CUSTOM_XML_FILE_NAME, EXTRACTED_FOLDER_NAME, UserPreference},
+    analyze_custom_xml::item_props::find_custom_xml_props,
+    analyze_custom_xml::main::{
+        CustomXmlFile, CustomXmlFormat, CustomXmlInfo, parse_custom_xml_content,
+    },
+    data_bindings::{main::DataBinding, refresh::refresh_data_bindings},
+    error::Error,
+    files::{is_file_custom_xml, read_struct_from_json},
+    sync_custom_xml::{
+        create::create_custom_xml_part,
+        delete::delete_custom_xml_part,
+        diff::{SyncDiff, diff_custom_xml_info},
+        patch::patch_custom_xml_content,
+        schema::{CustomXmlSchemaValidator, read_custom_xml_schemas},
+    },
+    types::{CUSTOM_XML_FILE_NAME, EXTRACTED_FOLDER_NAME},
 };
 
-/// Sync the customXml.json back to the customXml folder
-pub fn sync_custom_xml_wrapper(user_preference: &mut UserPreference) {
-    println!("\n");
-    let fn_name = "Sync customXML";
-    print_fn_progress(fn_name, "Syncing customXML...");
+/// The outcome of syncing the customXml.json back to the customXml folder
+#[derive(Debug, Default)]
+pub struct SyncResult {
+    /// The custom XML files that were rewritten
+    pub synced_files: Vec<String>,
+    /// The custom XML files that were added to the package, with their item props and relationships
+    pub created_files: Vec<String>,
+    /// The custom XML files that were removed from the package as they are no longer in customXml.json
+    pub deleted_files: Vec<String>,
+    /// The custom XML files that were left untouched as their content didn't change
+    pub skipped_files: Vec<String>,
+    /// The custom XML files that couldn't be written
+    pub failed_files: Vec<Error>,
+    /// The changes of each updated file between the disk and customXml.json
+    pub diffs: Vec<SyncDiff>,
+    /// Nothing was written, the files are listed by what would happen to them
+    pub dry_run: bool,
+    /// The bound content controls whose text was rewritten from the synced data, see [`refresh_data_bindings`]
+    pub refreshed_data_bindings: Vec<DataBinding>,
+}
 
-    let root_folder = get_extracted_root_folder_path(user_preference);
-    println!("Root folder: {}", root_folder);
+/// How [`sync_custom_xml`] handles the custom XML files missing on either side
+#[derive(Debug, Clone, Default)]
+pub struct SyncOptions {
+    /// Remove the custom XML parts that are no longer in customXml.json, see [`delete_custom_xml_part`]
+    pub delete_removed: bool,
+    /// Only report what would be created, updated, skipped or deleted without touching the extracted folder
+    pub dry_run: bool,
+    /// Rewrite the text of the content controls bound to the synced data, see [`refresh_data_bindings`]
+    pub refresh_data_bindings: bool,
+}
 
-    let sync_result = sync_custom_xml(&root_folder);
-    if let Err(e) = sync_result {
-        print_error_with_panic(e);
+impl SyncResult {
+    /// Total number of custom XML entries that were processed
+    pub fn total_count(&self) -> usize {
+        self.synced_files.len()
+            + self.created_files.len()
+            + self.deleted_files.len()
+            + self.skipped_files.len()
+            + self.failed_files.len()
     }
-
-    println!("{}", "Syncing customXML completed successfully!".green());
 }
 
 /// Sync the customXml.json back to the customXml folder inside the extracted folder
 ///
 /// ! Note that this will override the content of the customXml folder
 ///
-/// This also doesn't support syncing the item props and rels files
-pub fn sync_custom_xml(root_folder: &str) -> Result<(), &'static str> {
+/// The entries are matched to the files by the datastore item ID of their item props, so a package renumbered by Word
+/// still syncs to the right parts, and by file name when they don't have one. A file holding another datastore item than
+/// the entry's is never written, the entry is listed in [`SyncResult::failed_files`] instead.
+/// The files are updated in place, see [`patch_custom_xml_content`], so their declaration and formatting are kept.
+/// Entries without a matching file are created as new custom XML parts, see [`create_custom_xml_part`]. Files without a
+/// matching entry are left alone unless [`SyncOptions::delete_removed`] is set.
+///
+/// The changes of every updated file are listed in [`SyncResult::diffs`], with [`SyncOptions::dry_run`] that's all it does.
+/// With [`SyncOptions::refresh_data_bindings`] the content controls bound to the data show the synced values afterwards.
+///
+/// Entries whose JSON content doesn't match the schemas registered in customXmlSchemas.json are not written, they're
+/// listed in [`SyncResult::failed_files`] with the JSON pointers of the invalid values, see [`CustomXmlSchemaValidator`].
+pub fn sync_custom_xml(root_folder: &str, options: &SyncOptions) -> Result<SyncResult, Error> {
     // * Read the customXml.json file
     let custom_xml_json_path = format!("{}/{}", root_folder, CUSTOM_XML_FILE_NAME);
     if !Path::new(&custom_xml_json_path).exists() {
-        return Err("customXml.json file not found in the root folder");
+        return Err(Error::invalid_package(
+            root_folder,
+            "customXml.json file not found in the root folder",
+        ));
     }
 
-    let custom_xml_files: Vec<CustomXmlFile> = match read_struct_from_json(&custom_xml_json_path) {
-        Ok(files) => files,
-        Err(_) => {
-            return Err("Failed to read customXml.json");
-        }
-    };
+    let custom_xml_files: Vec<CustomXmlFile> = read_struct_from_json(&custom_xml_json_path)?;
 
-    if custom_xml_files.is_empty() {
-        return Err("No custom XML files found in customXml.json");
+    // Removing every entry is only meaningful when the removed parts get deleted
+    if custom_xml_files.is_empty() && !options.delete_removed {
+        return Err(Error::custom_xml_parse(
+            &custom_xml_json_path,
+            "No custom XML files found in customXml.json",
+        ));
     }
 
-    // * Get the customXml folder path inside extracted folder
-    let mut custom_xml_folder = format!("{}/{}/customXml", root_folder, EXTRACTED_FOLDER_NAME);
-    if !Path::new(&custom_xml_folder).exists() {
+    let extracted_folder = format!("{}/{}", root_folder, EXTRACTED_FOLDER_NAME);
+    if !Path::new(&extracted_folder).is_dir() {
+        return Err(Error::invalid_package(
+            root_folder,
+            "extracted folder not found in the root folder",
+        ));
+    }
+
+    // * Compile the registered schemas, nothing is written if they can't be used
+    let schemas = read_custom_xml_schemas(root_folder)?;
+    let schema_validator = CustomXmlSchemaValidator::new(root_folder, &schemas)?;
+
+    // * Get the customXml folder inside extracted folder, it's created along with the first new custom XML part
+    let mut custom_xml_folder_name = "customXml";
+    if !Path::new(&extracted_folder).join("customXml").exists()
+        && Path::new(&extracted_folder).join("customXML").exists()
+    {
         // Creating a custom xml via Word Desktop/Online might create a folder named customXML instead of customXml
         // #microsuck
-        custom_xml_folder = format!("{}/{}/customXML", root_folder, EXTRACTED_FOLDER_NAME);
+        custom_xml_folder_name = "customXML";
     }
+    let custom_xml_folder = format!("{}/{}", extracted_folder, custom_xml_folder_name);
 
-    // TODO: implement this feature later
-    // We'll need to also add the item props and rels files
-    let custom_xml_folder_path = Path::new(&custom_xml_folder);
-    if !custom_xml_folder_path.exists() {
-        return Err("customXml folder not found in the extracted folder");
-    }
+    // * Match the entries to the files by their datastore item ID, Word renumbers the custom XML parts when it saves
+    let item_ids_by_file_name =
+        get_item_ids_by_file_name(&extracted_folder, custom_xml_folder_name);
+    let entry_item_ids: HashSet<&str> = custom_xml_files.iter().filter_map(get_item_id).collect();
+    let mut matched_file_names: HashSet<String> = HashSet::new();
 
     // * Sync each custom XML file
-    let mut synced_count = 0;
-    let mut skipped_count = 0;
-    for custom_xml_file in &custom_xml_files {
-        let file_name = &custom_xml_file.file_info.file_name_with_extension;
+    let mut sync_result = SyncResult {
+        dry_run: options.dry_run,
+        ..Default::default()
+    };
+    for (index, custom_xml_file) in custom_xml_files.iter().enumerate() {
+        let item_id = get_item_id(custom_xml_file);
+        let matched_file_name = item_id.and_then(|item_id| {
+            item_ids_by_file_name
+                .iter()
+                .find(|(_, file_item_id)| file_item_id.as_deref() == Some(item_id))
+                .map(|(file_name, _)| file_name)
+        });
+        let file_name =
+            matched_file_name.unwrap_or(&custom_xml_file.file_info.file_name_with_extension);
+
+        // The file with the entry's name is another part: any other datastore item when the entry has an item ID,
+        //  one that's in customXml.json under its own item ID otherwise
+        let file_item_id = item_ids_by_file_name.get(file_name);
+        let is_other_part = match (item_id, file_item_id) {
+            (Some(_), Some(_)) => matched_file_name.is_none(),
+            (None, Some(Some(file_item_id))) => entry_item_ids.contains(file_item_id.as_str()),
+            _ => false,
+        };
+        if is_other_part {
+            let item = match file_item_id.cloned().flatten() {
+                Some(file_item_id) => format!("belongs to the datastore item {}", file_item_id),
+                None => "has no datastore item".to_string(),
+            };
+            sync_result.failed_files.push(Error::invalid_package(
+                format!("{}/{}", custom_xml_folder, file_name),
+                format!(
+                    "{} {}, the entry {} can't be synced to it",
+                    file_name,
+                    item,
+                    item_id.unwrap_or(file_name)
+                ),
+            ));
+            continue;
+        }
+        matched_file_names.insert(file_name.clone());
         let file_path = format!("{}/{}", custom_xml_folder, file_name);
 
+        // Invalid data is never written, the file keeps its current content
+        let violations = schema_validator.validate(index, custom_xml_file);
+        if !violations.is_empty() {
+            sync_result.failed_files.push(Error::SchemaValidation {
+                path: custom_xml_json_path.clone().into(),
+                violations,
+            });
+            continue;
+        }
+
+        if !Path::new(&file_path).exists() {
+            if options.dry_run {
+                sync_result.created_files.push(file_name.clone());
+                continue;
+            }
+            match create_custom_xml_part(&extracted_folder, custom_xml_folder_name, custom_xml_file)
+            {
+                Ok(_) => sync_result.created_files.push(file_name.clone()),
+                Err(e) => sync_result.failed_files.push(e),
+            }
+            continue;
+        }
+
         let should_update = should_update_file(&file_path, &custom_xml_file.custom_xml_info);
         if should_update {
-            // Reconstruct the XML content from the JSON data
-            let xml_content = reconstruct_xml_from_json(custom_xml_file);
-
-            // Write the XML content to the file
-            match fs::write(&file_path, xml_content) {
-                Ok(_) => {
-                    synced_count += 1;
-                    println!("Synced: {}", file_name);
+            // Only the parts that changed are replaced in the current content, it's rebuilt from the JSON data if it can't be read
+            let xml_content = match fs::read_to_string(&file_path) {
+                Ok(current_content) => {
+                    if let Ok(current_info) = parse_custom_xml_content(&current_content) {
+                        sync_result.diffs.push(SyncDiff {
+                            file_name: file_name.clone(),
+                            changes: diff_custom_xml_info(
+                                &current_info,
+                                &custom_xml_file.custom_xml_info,
+                            ),
+                        });
+                    }
+                    patch_custom_xml_content(&current_content, &custom_xml_file.custom_xml_info)
                 }
+                Err(_) => reconstruct_xml_from_json(custom_xml_file),
+            };
+            let xml_content = match xml_content {
+                Ok(xml_content) => xml_content,
                 Err(e) => {
-                    println!(
-                        "{}",
-                        get_error_message(&format!("Failed to write {}: {}", file_name, e))
-                    );
+                    sync_result
+                        .failed_files
+                        .push(Error::custom_xml_parse(&file_path, e));
+                    continue;
                 }
+            };
+            if options.dry_run {
+                sync_result.synced_files.push(file_name.clone());
+                continue;
+            }
+
+            // Write the XML content to the file
+            match fs::write(&file_path, xml_content) {
+                Ok(_) => sync_result.synced_files.push(file_name.clone()),
+                Err(e) => sync_result.failed_files.push(Error::io(&file_path, e)),
             }
         } else {
-            skipped_count += 1;
-            println!("Skipped (unchanged): {}", file_name);
+            sync_result.skipped_files.push(file_name.clone());
+        }
+    }
+
+    // * Delete the custom XML files that are no longer in customXml.json
+    if options.delete_removed {
+        let removed_file_names: Vec<&String> = item_ids_by_file_name
+            .keys()
+            .filter(|file_name| !matched_file_names.contains(*file_name))
+            .collect();
+
+        for file_name in removed_file_names {
+            if options.dry_run {
+                sync_result.deleted_files.push(file_name.clone());
+                continue;
+            }
+            let item_part_name = format!("{}/{}", custom_xml_folder_name, file_name);
+            match delete_custom_xml_part(&extracted_folder, &item_part_name) {
+                Ok(_) => sync_result.deleted_files.push(file_name.clone()),
+                Err(e) => sync_result.failed_files.push(e),
+            }
+        }
+    }
+
+    // * Show the synced values in the bound content controls
+    if options.refresh_data_bindings && !options.dry_run {
+        match refresh_data_bindings(&extracted_folder) {
+            Ok(refreshed_data_bindings) => {
+                sync_result.refreshed_data_bindings = refreshed_data_bindings
+            }
+            Err(e) => sync_result.failed_files.push(e),
         }
     }
 
-    println!(
-        "Successfully synced {} out of {} custom XML files ({} skipped as unchanged)",
-        synced_count,
-        custom_xml_files.len(),
-        skipped_count
-    );
-    Ok(())
+    Ok(sync_result)
+}
+
+/// The datastore item ID of the entry, `None` for entries written before item props were recorded
+fn get_item_id(custom_xml_file: &CustomXmlFile) -> Option<&str> {
+    custom_xml_file
+        .item_props
+        .as_ref()
+        .map(|item_props| item_props.item_id.as_str())
+}
+
+/// The custom XML files in the custom XML folder, with the datastore item ID of their item props if they have any
+fn get_item_ids_by_file_name(
+    extracted_folder: &str,
+    custom_xml_folder_name: &str,
+) -> BTreeMap<String, Option<String>> {
+    let mut item_ids_by_file_name = BTreeMap::new();
+    let Ok(entries) = fs::read_dir(Path::new(extracted_folder).join(custom_xml_folder_name)) else {
+        return item_ids_by_file_name;
+    };
+
+    for entry in entries.flatten() {
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        if !is_file_custom_xml(&file_name) {
+            continue;
+        }
+        let item_part_name = format!("{}/{}", custom_xml_folder_name, file_name);
+        let item_props = find_custom_xml_props(&item_part_name, |part_name| {
+            fs::read_to_string(Path::new(extracted_folder).join(part_name)).ok()
+        });
+        item_ids_by_file_name.insert(file_name, item_props.map(|item_props| item_props.item_id));
+    }
+
+    item_ids_by_file_name
 }
 
 /// Determine if a file should be updated by comparing the current file content with the expected CustomXmlInfo
 ///
 /// This only return `true` if the content changed.
-/// If the file is not found or the content can't be parsed, this will return `false` as well as we'll have to also check whether the rel files exist or not
-/// TODO: implement this feature later
+/// If the file is not found or the content can't be parsed, this will return `false` as well, [`sync_custom_xml`] creates
+/// the missing files along with their item props and rels files instead
 pub fn should_update_file(file_path: &str, expected_info: &CustomXmlInfo) -> bool {
     if !Path::new(file_path).exists() {
         println!("{}", format!("File not found: {}", file_path).yellow());
@@ -119,7 +318,7 @@ pub fn should_update_file(file_path: &str, expected_info: &CustomXmlInfo) -> boo
     match fs::read_to_string(file_path) {
         Ok(current_content) => {
             // Parse the current XML content
-            match parse_custom_xml_content_for_tag(&current_content) {
+            match parse_custom_xml_content(&current_content) {
                 Ok(current_info) => {
                     // Compare with the expected version
                     current_info != *expected_info
@@ -128,7 +327,7 @@ pub fn should_update_file(file_path: &str, expected_info: &CustomXmlInfo) -> boo
                     // If parsing fails, we should update
                     println!(
                         "{}",
-                        get_error_message(&format!("Failed to parse {}: {}", file_path, error))
+                        format!("Failed to parse {}: {}", file_path, error).red()
                     );
                     false
                 }
@@ -138,7 +337,7 @@ pub fn should_update_file(file_path: &str, expected_info: &CustomXmlInfo) -> boo
             // If reading fails, we should update
             println!(
                 "{}",
-                get_error_message(&format!("Failed to read {}: {}", file_path, error))
+                format!("Failed to read {}: {}", file_path, error).red()
             );
             false
         }
@@ -146,8 +345,16 @@ pub fn should_update_file(file_path: &str, expected_info: &CustomXmlInfo) -> boo
 }
 
 /// Reconstruct the XML content from the CustomXmlFile struct
-fn reconstruct_xml_from_json(custom_xml_file: &CustomXmlFile) -> String {
+///
+/// A [`CustomXmlFormat::Xml`] info is rebuilt from its nodes and JSON elements, see [`CustomXmlInfo::to_xml_element`]
+fn reconstruct_xml_from_json(custom_xml_file: &CustomXmlFile) -> Result<String, String> {
     let custom_xml_info = &custom_xml_file.custom_xml_info;
+    if custom_xml_info.format == CustomXmlFormat::Xml {
+        return custom_xml_info
+            .to_xml_element()
+            .map(|root| root.to_xml_string());
+    }
+
     let tag = &custom_xml_info.tag;
     let attributes = &custom_xml_info.attributes;
     let json_content = &custom_xml_info.json_content;
@@ -163,7 +370,7 @@ fn reconstruct_xml_from_json(custom_xml_file: &CustomXmlFile) -> String {
     {
         for (key, value) in attrs_obj {
             if let Some(value_str) = value.as_str() {
-                let attribute_string = format!(" {}=\"{}\"", key, value_str);
+                let attribute_string = format!(" {}=\"{}\"", key, escape(value_str));
                 xml.push_str(attribute_string.as_str());
             }
         }
@@ -171,22 +378,21 @@ fn reconstruct_xml_from_json(custom_xml_file: &CustomXmlFile) -> String {
 
     xml.push('>');
 
-    // * Add the JSON content (no pretty printing)
+    // * Add the JSON content (no pretty printing), the quotes don't need to be escaped in a text node
     let json_content_str = serde_json::to_string(json_content).unwrap();
-    xml.push_str(&json_content_str);
+    xml.push_str(&partial_escape(json_content_str));
 
     // * Add the closing tag
     xml.push_str("</");
     xml.push_str(tag);
     xml.push('>');
 
-    xml
+    Ok(xml)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::utils::analyze_custom_xml::main::CustomXmlInfo;
     use crate::utils::types::FileInfo;
     use serde_json::json;
 
@@ -198,17 +404,20 @@ mod tests {
                 full_file_path: "test/item1.xml".to_string(),
                 file_size_in_kb: 1.0,
             },
+            item_props: None,
             custom_xml_info: CustomXmlInfo {
                 tag: "rootTag".to_string(),
+                format: CustomXmlFormat::Json,
                 attributes: Some(json!({
                     "attr1": "value1",
                     "attr2": "value2"
                 })),
                 json_content: json!({"key": "value"}),
+                elements: Vec::new(),
             },
         };
 
-        let result = reconstruct_xml_from_json(&custom_xml_file);
+        let result = reconstruct_xml_from_json(&custom_xml_file).unwrap();
 
         assert!(result.starts_with("<rootTag"));
         assert!(result.contains("attr1=\"value1\""));
@@ -225,14 +434,17 @@ mod tests {
                 full_file_path: "test/item1.xml".to_string(),
                 file_size_in_kb: 1.0,
             },
+            item_props: None,
             custom_xml_info: CustomXmlInfo {
                 tag: "simpleTag".to_string(),
+                format: CustomXmlFormat::Json,
                 attributes: None,
                 json_content: json!({"data": 123}),
+                elements: Vec::new(),
             },
         };
 
-        let result = reconstruct_xml_from_json(&custom_xml_file);
+        let result = reconstruct_xml_from_json(&custom_xml_file).unwrap();
 
         assert_eq!(result, "<simpleTag>{\"data\":123}</simpleTag>");
     }
@@ -245,14 +457,17 @@ mod tests {
                 full_file_path: "test/item1.xml".to_string(),
                 file_size_in_kb: 1.0,
             },
+            item_props: None,
             custom_xml_info: CustomXmlInfo {
                 tag: "emptyTag".to_string(),
+                format: CustomXmlFormat::Json,
                 attributes: Some(json!({"id": "123"})),
                 json_content: json!({}),
+                elements: Vec::new(),
             },
         };
 
-        let result = reconstruct_xml_from_json(&custom_xml_file);
+        let result = reconstruct_xml_from_json(&custom_xml_file).unwrap();
 
         assert!(result.starts_with("<emptyTag"));
         assert!(result.contains("id=\"123\""));
@@ -268,8 +483,10 @@ mod tests {
                 full_file_path: "test/item1.xml".to_string(),
                 file_size_in_kb: 1.0,
             },
+            item_props: None,
             custom_xml_info: CustomXmlInfo {
                 tag: "dataTag".to_string(),
+                format: CustomXmlFormat::Json,
                 attributes: Some(json!({"version": "1.0"})),
                 json_content: json!({
                     "nested": {
@@ -278,10 +495,11 @@ mod tests {
                     },
                     "array": [1, 2, 3]
                 }),
+                elements: Vec::new(),
             },
         };
 
-        let result = reconstruct_xml_from_json(&custom_xml_file);
+        let result = reconstruct_xml_from_json(&custom_xml_file).unwrap();
 
         assert!(result.starts_with("<dataTag"));
         assert!(result.contains("version=\"1.0\""));
@@ -298,14 +516,17 @@ mod tests {
                 full_file_path: "test/item1.xml".to_string(),
                 file_size_in_kb: 1.0,
             },
+            item_props: None,
             custom_xml_info: CustomXmlInfo {
                 tag: "singleAttr".to_string(),
+                format: CustomXmlFormat::Json,
                 attributes: Some(json!({"name": "test"})),
                 json_content: json!("simple string"),
+                elements: Vec::new(),
             },
         };
 
-        let result = reconstruct_xml_from_json(&custom_xml_file);
+        let result = reconstruct_xml_from_json(&custom_xml_file).unwrap();
 
         assert!(result.starts_with("<singleAttr"));
         assert!(result.contains("name=\"test\""));
@@ -313,12 +534,69 @@ mod tests {
         assert!(result.ends_with("</singleAttr>"));
     }
 
+    #[test]
+    fn test_reconstruct_xml_from_json_escapes_special_characters() {
+        let custom_xml_info = CustomXmlInfo {
+            tag: "ns0:root".to_string(),
+            format: CustomXmlFormat::Json,
+            attributes: Some(json!({"title": "a & \"b\""})),
+            json_content: json!({"key": "<value> & more"}),
+            elements: Vec::new(),
+        };
+        let custom_xml_file = CustomXmlFile {
+            file_info: FileInfo {
+                file_name_with_extension: "item1.xml".to_string(),
+                full_file_path: "test/item1.xml".to_string(),
+                file_size_in_kb: 1.0,
+            },
+            item_props: None,
+            custom_xml_info,
+        };
+
+        let result = reconstruct_xml_from_json(&custom_xml_file).unwrap();
+
+        assert_eq!(
+            result,
+            "<ns0:root title=\"a &amp; &quot;b&quot;\">{\"key\":\"&lt;value&gt; &amp; more\"}</ns0:root>"
+        );
+        // Parsing it back gives the same info
+        assert_eq!(
+            parse_custom_xml_content(&result).unwrap(),
+            custom_xml_file.custom_xml_info
+        );
+    }
+
+    #[test]
+    fn test_reconstruct_xml_from_json_elements() {
+        let mut custom_xml_info =
+            parse_custom_xml_content("<root><config>{}</config><data>[]</data></root>").unwrap();
+        custom_xml_info.elements[1].json_content = json!(["a & b"]);
+        let custom_xml_file = CustomXmlFile {
+            file_info: FileInfo {
+                file_name_with_extension: "item1.xml".to_string(),
+                full_file_path: "test/item1.xml".to_string(),
+                file_size_in_kb: 1.0,
+            },
+            item_props: None,
+            custom_xml_info,
+        };
+
+        let result = reconstruct_xml_from_json(&custom_xml_file).unwrap();
+
+        assert_eq!(
+            result,
+            "<root><config>{}</config><data>[\"a &amp; b\"]</data></root>"
+        );
+    }
+
     #[test]
     fn test_should_update_file_when_file_does_not_exist() {
         let expected_info = CustomXmlInfo {
             tag: "testTag".to_string(),
+            format: CustomXmlFormat::Json,
             attributes: None,
             json_content: json!({"key": "value"}),
+            elements: Vec::new(),
         };
 
         // Use a non-existent file path
@@ -335,8 +613,10 @@ mod tests {
 
         let expected_info = CustomXmlInfo {
             tag: "testTag".to_string(),
+            format: CustomXmlFormat::Json,
             attributes: Some(json!({"attr1": "value1"})),
             json_content: json!({"key": "value"}),
+            elements: Vec::new(),
         };
 
         // Create a temporary file with matching content
@@ -359,8 +639,10 @@ mod tests {
 
         let expected_info = CustomXmlInfo {
             tag: "testTag".to_string(),
+            format: CustomXmlFormat::Json,
             attributes: Some(json!({"attr1": "value1"})),
             json_content: json!({"key": "different_value"}),
+            elements: Vec::new(),
         };
 
         // Create a temporary file with different content
@@ -383,8 +665,10 @@ mod tests {
 
         let expected_info = CustomXmlInfo {
             tag: "testTag".to_string(),
+            format: CustomXmlFormat::Json,
             attributes: None,
             json_content: json!({"key": "value"}),
+            elements: Vec::new(),
         };
 
         // Create a temporary file with different tag
@@ -407,8 +691,10 @@ mod tests {
 
         let expected_info = CustomXmlInfo {
             tag: "testTag".to_string(),
+            format: CustomXmlFormat::Json,
             attributes: Some(json!({"attr1": "value1"})),
             json_content: json!({"key": "value"}),
+            elements: Vec::new(),
         };
 
         // Create a temporary file with different attributes
@@ -431,8 +717,10 @@ mod tests {
 
         let expected_info = CustomXmlInfo {
             tag: "testTag".to_string(),
+            format: CustomXmlFormat::Json,
             attributes: None,
             json_content: json!({"key": "value"}),
+            elements: Vec::new(),
         };
 
         // Create a temporary file with invalid XML
@@ -458,8 +746,10 @@ mod tests {
 
         let expected_info = CustomXmlInfo {
             tag: "testTag".to_string(),
+            format: CustomXmlFormat::Json,
             attributes: Some(json!({"attr1": "value1"})),
             json_content: json!({"key": "value"}),
+            elements: Vec::new(),
         };
 
         // Create a temporary file without attributes
@@ -485,8 +775,10 @@ mod tests {
 
         let expected_info = CustomXmlInfo {
             tag: "testTag".to_string(),
+            format: CustomXmlFormat::Json,
             attributes: None,
             json_content: json!({"key": "value"}),
+            elements: Vec::new(),
         };
 
         // Create a temporary file with attributes
@@ -504,4 +796,144 @@ mod tests {
             "Should return true when attributes are present in file but not expected"
         );
     }
+
+    #[test]
+    fn test_sync_custom_xml_matches_item_id() {
+        use crate::utils::{
+            analyze_custom_xml::item_props::CustomXmlProps, files::write_struct_to_json,
+        };
+        use tempfile::tempdir;
+
+        // Word renumbered the parts, the entry's part is item2.xml now
+        let temp_dir = tempdir().unwrap();
+        let root_folder = temp_dir.path().to_str().unwrap();
+        let custom_xml_folder = temp_dir.path().join("extracted/customXml");
+        fs::create_dir_all(&custom_xml_folder).unwrap();
+        for (index, item_id) in [(1, "{B}"), (2, "{A}")] {
+            fs::write(
+                custom_xml_folder.join(format!("item{}.xml", index)),
+                r#"<root>{"key":"value"}</root>"#,
+            )
+            .unwrap();
+            fs::write(
+                custom_xml_folder.join(format!("itemProps{}.xml", index)),
+                format!(
+                    r#"<ds:datastoreItem ds:itemID="{}" xmlns:ds="urn:ds"/>"#,
+                    item_id
+                ),
+            )
+            .unwrap();
+        }
+
+        let custom_xml_files = vec![CustomXmlFile {
+            file_info: FileInfo {
+                file_name_with_extension: "item1.xml".to_string(),
+                full_file_path: "customXml/item1.xml".to_string(),
+                file_size_in_kb: 0.0,
+            },
+            item_props: Some(CustomXmlProps {
+                item_id: "{A}".to_string(),
+                schema_refs: Vec::new(),
+            }),
+            custom_xml_info: parse_custom_xml_content(r#"<root>{"key":"updated"}</root>"#).unwrap(),
+        }];
+        write_struct_to_json(
+            &custom_xml_files,
+            &format!("{}/{}", root_folder, CUSTOM_XML_FILE_NAME),
+        )
+        .unwrap();
+
+        let sync_result = sync_custom_xml(root_folder, &SyncOptions::default()).unwrap();
+
+        assert_eq!(sync_result.synced_files, vec!["item2.xml"]);
+        assert_eq!(
+            fs::read_to_string(custom_xml_folder.join("item2.xml")).unwrap(),
+            r#"<root>{"key":"updated"}</root>"#
+        );
+        assert_eq!(
+            fs::read_to_string(custom_xml_folder.join("item1.xml")).unwrap(),
+            r#"<root>{"key":"value"}</root>"#
+        );
+
+        // item1.xml is another part that isn't in customXml.json anymore
+        let options = SyncOptions {
+            delete_removed: true,
+            dry_run: true,
+            ..Default::default()
+        };
+        let sync_result = sync_custom_xml(root_folder, &options).unwrap();
+        assert_eq!(sync_result.skipped_files, vec!["item2.xml"]);
+        assert_eq!(sync_result.deleted_files, vec!["item1.xml"]);
+
+        // The entry's item isn't in the package anymore, item1.xml holds another datastore item that isn't listed
+        let mut custom_xml_files = custom_xml_files;
+        custom_xml_files[0].item_props.as_mut().unwrap().item_id = "{C}".to_string();
+        write_struct_to_json(
+            &custom_xml_files,
+            &format!("{}/{}", root_folder, CUSTOM_XML_FILE_NAME),
+        )
+        .unwrap();
+
+        let sync_result = sync_custom_xml(root_folder, &SyncOptions::default()).unwrap();
+        assert!(sync_result.synced_files.is_empty());
+        assert_eq!(sync_result.failed_files.len(), 1);
+        assert_eq!(
+            fs::read_to_string(custom_xml_folder.join("item1.xml")).unwrap(),
+            r#"<root>{"key":"value"}</root>"#
+        );
+    }
+
+    #[test]
+    fn test_sync_custom_xml_rejects_schema_violations() {
+        use crate::utils::{files::write_struct_to_json, types::CUSTOM_XML_SCHEMAS_FILE_NAME};
+        use tempfile::tempdir;
+
+        let temp_dir = tempdir().unwrap();
+        let root_folder = temp_dir.path().to_str().unwrap();
+        let custom_xml_folder = temp_dir.path().join("extracted/customXml");
+        fs::create_dir_all(&custom_xml_folder).unwrap();
+        fs::write(
+            custom_xml_folder.join("item1.xml"),
+            r#"<root>{"count":1}</root>"#,
+        )
+        .unwrap();
+        fs::write(
+            temp_dir.path().join(CUSTOM_XML_SCHEMAS_FILE_NAME),
+            r#"{"tags": {"root": {"properties": {"count": {"type": "integer"}}}}}"#,
+        )
+        .unwrap();
+
+        let custom_xml_files = vec![CustomXmlFile {
+            file_info: FileInfo {
+                file_name_with_extension: "item1.xml".to_string(),
+                full_file_path: "customXml/item1.xml".to_string(),
+                file_size_in_kb: 0.0,
+            },
+            item_props: None,
+            custom_xml_info: parse_custom_xml_content(r#"<root>{"count":"two"}</root>"#).unwrap(),
+        }];
+        write_struct_to_json(
+            &custom_xml_files,
+            &format!("{}/{}", root_folder, CUSTOM_XML_FILE_NAME),
+        )
+        .unwrap();
+
+        let sync_result = sync_custom_xml(root_folder, &SyncOptions::default()).unwrap();
+
+        assert!(sync_result.synced_files.is_empty());
+        match &sync_result.failed_files[..] {
+            [Error::SchemaValidation { violations, .. }] => {
+                assert_eq!(violations.len(), 1);
+                assert_eq!(
+                    violations[0].pointer,
+                    "/0/custom_xml_info/json_content/count"
+                );
+            }
+            failed_files => panic!("Unexpected failures {:?}", failed_files),
+        }
+        assert_eq!(
+            fs::read_to_string(custom_xml_folder.join("item1.xml")).unwrap(),
+            r#"<root>{"count":1}</root>"#
+        );
+    }
 }
@@ -1 +1,6 @@
+pub mod create;
+pub mod delete;
+pub mod diff;
 pub mod main;
+pub mod patch;
+pub mod schema;
@@ -0,0 +1,250 @@
+use quick_xml::escape::{escape, partial_escape};
+use serde::Serialize;
+use std::mem::discriminant;
+
+use crate::utils::{
+    analyze_custom_xml::main::CustomXmlInfo,
+    xml::{XmlEdit, XmlElement, XmlNode, XmlSpan, apply_xml_edits},
+};
+
+/// Update the original content of a custom XML file so it holds the info, only the parts that changed are replaced
+///
+/// Everything else (the XML declaration, `xmlns` declarations, attribute order, quotes and whitespace) is kept byte for
+/// byte. A JSON body that changed keeps its surrounding whitespace and its key order, and is pretty printed with the
+/// original indentation if it was before.
+pub fn patch_custom_xml_content(source: &str, info: &CustomXmlInfo) -> Result<String, String> {
+    let (original, span) = XmlElement::parse_with_spans(source)?;
+    let updated = info.to_xml_element()?;
+
+    let mut edits: Vec<XmlEdit> = Vec::new();
+    diff_element(source, &original, &span, &updated, &mut edits);
+
+    Ok(apply_xml_edits(source, edits))
+}
+
+fn diff_element(
+    source: &str,
+    original: &XmlElement,
+    span: &XmlSpan,
+    updated: &XmlElement,
+    edits: &mut Vec<XmlEdit>,
+) {
+    // * A renamed element, or children added to an empty element (`<a/>`), is written again as a whole
+    if original.tag != updated.tag || (span.content.is_none() && !updated.children.is_empty()) {
+        edits.push((span.range.clone(), updated.to_xml_string()));
+        return;
+    }
+
+    // * Attributes are updated or removed in place, the new ones go after the last attribute
+    for attribute_span in &span.attributes {
+        let original_value = original.attributes.get(&attribute_span.name);
+        match updated.attributes.get(&attribute_span.name) {
+            Some(value) if original_value != Some(value) => {
+                edits.push((attribute_span.value.clone(), escape(value).to_string()));
+            }
+            Some(_) => {}
+            None => {
+                // The whitespace before the attribute goes with it
+                let start = source[..attribute_span.range.start].trim_end().len();
+                edits.push((start..attribute_span.range.end, String::new()));
+            }
+        }
+    }
+
+    let added_attributes: String = updated
+        .attributes
+        .iter()
+        .filter(|(name, _)| !original.attributes.contains_key(*name))
+        .map(|(name, value)| format!(" {}=\"{}\"", name, escape(value)))
+        .collect();
+    if !added_attributes.is_empty() {
+        edits.push((span.attributes_end..span.attributes_end, added_attributes));
+    }
+
+    // * Children are compared one by one if they are the same kind of nodes, otherwise the content is written again
+    let is_same_structure = original.children.len() == updated.children.len()
+        && original.children.iter().zip(&updated.children).all(
+            |(original_child, updated_child)| {
+                discriminant(original_child) == discriminant(updated_child)
+            },
+        );
+    if !is_same_structure {
+        if let Some(content) = &span.content {
+            let content_xml: String = updated
+                .children
+                .iter()
+                .map(|child| child.to_xml_string())
+                .collect();
+            edits.push((content.clone(), content_xml));
+        }
+        return;
+    }
+
+    for ((original_child, child_span), updated_child) in original
+        .children
+        .iter()
+        .zip(&span.children)
+        .zip(&updated.children)
+    {
+        match (original_child, updated_child) {
+            (XmlNode::Element(original_element), XmlNode::Element(updated_element)) => {
+                diff_element(source, original_element, child_span, updated_element, edits);
+            }
+            (XmlNode::Text(original_text), XmlNode::Text(updated_text)) => {
+                if let Some(text) = diff_text(original_text, updated_text) {
+                    edits.push((child_span.range.clone(), text));
+                }
+            }
+            _ if original_child != updated_child => {
+                edits.push((child_span.range.clone(), updated_child.to_xml_string()));
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The escaped text replacing the original text, `None` if the text didn't change
+///
+/// JSON texts are compared by value, so a pretty printed JSON isn't replaced by the same JSON in one line
+fn diff_text(original_text: &str, updated_text: &str) -> Option<String> {
+    if original_text == updated_text {
+        return None;
+    }
+
+    let original_json = serde_json::from_str::<serde_json::Value>(original_text);
+    let updated_json = serde_json::from_str::<serde_json::Value>(updated_text);
+    let (Ok(original_json), Ok(updated_json)) = (original_json, updated_json) else {
+        return Some(partial_escape(updated_text).to_string());
+    };
+    if original_json == updated_json {
+        return None;
+    }
+
+    // Keep the whitespace around the JSON and its style
+    let trimmed_start = original_text.trim_start();
+    let leading_whitespace = &original_text[..original_text.len() - trimmed_start.len()];
+    let trailing_whitespace = &trimmed_start[trimmed_start.trim_end().len()..];
+    let original_body = trimmed_start.trim_end();
+    let json = if original_body.contains('\n') {
+        to_string_indented(&updated_json, original_body)
+    } else {
+        serde_json::to_string(&updated_json).unwrap()
+    };
+
+    Some(format!(
+        "{}{}{}",
+        leading_whitespace,
+        partial_escape(&json),
+        trailing_whitespace
+    ))
+}
+
+/// Pretty print the JSON like the original body: the lines after the first start at the indentation of its closing
+/// bracket, and nest with the same indent unit (2 spaces if it can't be told)
+fn to_string_indented(json: &serde_json::Value, original_body: &str) -> String {
+    let get_indentation = |line: &str| line[..line.len() - line.trim_start().len()].to_string();
+    let lines: Vec<&str> = original_body.lines().collect();
+    let base_indentation = lines
+        .last()
+        .map(|line| get_indentation(line))
+        .unwrap_or_default();
+    let indent_unit = lines
+        .get(1)
+        .and_then(|line| {
+            get_indentation(line)
+                .strip_prefix(&base_indentation)
+                .map(String::from)
+        })
+        .filter(|indent_unit| !indent_unit.is_empty())
+        .unwrap_or_else(|| "  ".to_string());
+
+    let mut pretty = Vec::new();
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(indent_unit.as_bytes());
+    let mut serializer = serde_json::Serializer::with_formatter(&mut pretty, formatter);
+    json.serialize(&mut serializer).unwrap();
+
+    String::from_utf8(pretty)
+        .unwrap()
+        .replace('\n', &format!("\n{}", base_indentation))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::analyze_custom_xml::main::parse_custom_xml_content;
+    use serde_json::json;
+
+    #[test]
+    fn test_patch_custom_xml_content_json_body() {
+        let source = "<?xml version=\"1.0\" standalone=\"no\"?>\r\n<ns0:root xmlns:ns0='urn:x' b=\"2\" a=\"1\">\n  {\"key\": \"value\", \"other\": 1}\n</ns0:root>";
+        let mut info = parse_custom_xml_content(source).unwrap();
+
+        // Nothing changed
+        assert_eq!(patch_custom_xml_content(source, &info).unwrap(), source);
+
+        info.json_content["key"] = json!("<new>");
+        assert_eq!(
+            patch_custom_xml_content(source, &info).unwrap(),
+            "<?xml version=\"1.0\" standalone=\"no\"?>\r\n<ns0:root xmlns:ns0='urn:x' b=\"2\" a=\"1\">\n  {\"key\":\"&lt;new&gt;\",\"other\":1}\n</ns0:root>"
+        );
+
+        info.json_content = json!({ "key": "value", "other": 1 });
+        info.attributes = Some(json!({ "xmlns:ns0": "urn:x", "b": "3", "c": "&" }));
+        assert_eq!(
+            patch_custom_xml_content(source, &info).unwrap(),
+            "<?xml version=\"1.0\" standalone=\"no\"?>\r\n<ns0:root xmlns:ns0='urn:x' b=\"3\" c=\"&amp;\">\n  {\"key\": \"value\", \"other\": 1}\n</ns0:root>"
+        );
+    }
+
+    #[test]
+    fn test_patch_custom_xml_content_xml_nodes() {
+        let source = r#"<root>
+    <!-- settings -->
+    <config>
+        {
+            "zoom": 1,
+            "debug": false
+        }
+    </config>
+    <title lang="en">Hello</title>
+    <empty/>
+</root>
+"#;
+        let mut info = parse_custom_xml_content(source).unwrap();
+        info.elements[0].json_content["debug"] = json!(true);
+        info.json_content[2] =
+            json!({ "tag": "title", "attributes": { "lang": "en" }, "children": ["Hi & bye"] });
+        info.json_content[3] = json!({ "tag": "empty", "children": ["filled"] });
+
+        assert_eq!(
+            patch_custom_xml_content(source, &info).unwrap(),
+            r#"<root>
+    <!-- settings -->
+    <config>
+        {
+            "zoom": 1,
+            "debug": true
+        }
+    </config>
+    <title lang="en">Hi &amp; bye</title>
+    <empty>filled</empty>
+</root>
+"#
+        );
+    }
+
+    #[test]
+    fn test_patch_custom_xml_content_mixed_content() {
+        let source = "<?xml version=\"1.0\"?>\n<!-- generated -->\n<p>\n    <r><b>a</b> <i>b</i></r>\n</p>\n";
+        let mut info = parse_custom_xml_content(source).unwrap();
+
+        // The space between the runs is content, the nodes around the root are kept by the patch
+        assert_eq!(patch_custom_xml_content(source, &info).unwrap(), source);
+
+        info.json_content[0]["children"][2] = json!({ "tag": "i", "children": ["c"] });
+        assert_eq!(
+            patch_custom_xml_content(source, &info).unwrap(),
+            "<?xml version=\"1.0\"?>\n<!-- generated -->\n<p>\n    <r><b>a</b> <i>c</i></r>\n</p>\n"
+        );
+    }
+}
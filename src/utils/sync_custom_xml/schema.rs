@@ -0,0 +1,313 @@
+use jsonschema::Validator;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+
+use crate::utils::{
+    analyze_custom_xml::main::{CustomXmlFile, CustomXmlFormat},
+    error::Error,
+    files::{read_struct_from_json, write_struct_to_json},
+    types::CUSTOM_XML_SCHEMAS_FILE_NAME,
+};
+
+/// The JSON Schemas the JSON content of the custom XML files has to match, registered in customXmlSchemas.json
+///
+/// A schema is either inline or the path of a schema file relative to the root folder, e.g.
+/// `{"tags": {"ns0:invoice": "schemas/invoice.json"}, "schema_refs": {"urn:invoice": {"type": "object"}}}`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CustomXmlSchemas {
+    /// The schemas by tag, for the root of a [`CustomXmlFormat::Json`] file or an element holding JSON
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tags: BTreeMap<String, Value>,
+    /// The schemas by schema reference of the item props, for the root of a [`CustomXmlFormat::Json`] file
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub schema_refs: BTreeMap<String, Value>,
+}
+
+/// A JSON content that doesn't match its schema
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaViolation {
+    /// The JSON pointer of the invalid value in customXml.json, e.g. `/0/custom_xml_info/json_content/items/1`
+    pub pointer: String,
+    pub message: String,
+}
+
+impl fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.pointer, self.message)
+    }
+}
+
+/// What a schema registered with [`register_custom_xml_schema`] applies to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchemaTarget {
+    /// The custom XML files and elements with this tag, e.g. `ns0:invoice`
+    Tag(String),
+    /// The custom XML files whose item props reference this schema, e.g. `urn:invoice`
+    SchemaRef(String),
+}
+
+/// The compiled [`CustomXmlSchemas`] of a root folder
+#[derive(Debug, Default)]
+pub struct CustomXmlSchemaValidator {
+    tags: BTreeMap<String, Validator>,
+    schema_refs: BTreeMap<String, Validator>,
+}
+
+/// Read the customXmlSchemas.json of the root folder, no schema is registered when it doesn't exist
+pub fn read_custom_xml_schemas(root_folder: &str) -> Result<CustomXmlSchemas, Error> {
+    let schemas_path = format!("{}/{}", root_folder, CUSTOM_XML_SCHEMAS_FILE_NAME);
+    if !Path::new(&schemas_path).exists() {
+        return Ok(CustomXmlSchemas::default());
+    }
+
+    read_struct_from_json(&schemas_path)
+}
+
+/// Register a schema in the customXmlSchemas.json of the root folder, replacing the one of the same target
+///
+/// The schema is inline or the path of a schema file relative to the root folder, it's compiled first so an invalid
+/// schema is never registered
+pub fn register_custom_xml_schema(
+    root_folder: &str,
+    target: &SchemaTarget,
+    schema: Value,
+) -> Result<(), Error> {
+    let registered = match target {
+        SchemaTarget::Tag(tag) => CustomXmlSchemas {
+            tags: BTreeMap::from([(tag.clone(), schema)]),
+            ..Default::default()
+        },
+        SchemaTarget::SchemaRef(schema_ref) => CustomXmlSchemas {
+            schema_refs: BTreeMap::from([(schema_ref.clone(), schema)]),
+            ..Default::default()
+        },
+    };
+    CustomXmlSchemaValidator::new(root_folder, &registered)?;
+
+    let mut schemas = read_custom_xml_schemas(root_folder)?;
+    schemas.tags.extend(registered.tags);
+    schemas.schema_refs.extend(registered.schema_refs);
+    write_struct_to_json(
+        &schemas,
+        &format!("{}/{}", root_folder, CUSTOM_XML_SCHEMAS_FILE_NAME),
+    )
+}
+
+impl CustomXmlSchemaValidator {
+    /// Compile the schemas of customXmlSchemas.json, loading the ones registered by path
+    pub fn new(root_folder: &str, schemas: &CustomXmlSchemas) -> Result<Self, Error> {
+        let schemas_path = format!("{}/{}", root_folder, CUSTOM_XML_SCHEMAS_FILE_NAME);
+        let compile = |schemas: &BTreeMap<String, Value>| {
+            schemas
+                .iter()
+                .map(|(key, schema)| {
+                    let validator = match schema {
+                        Value::String(schema_file) => {
+                            let schema_file_path = format!("{}/{}", root_folder, schema_file);
+                            let schema: Value = read_struct_from_json(&schema_file_path)?;
+                            compile_schema(&schema_file_path, &schema)?
+                        }
+                        schema => compile_schema(&schemas_path, schema)?,
+                    };
+                    Ok((key.clone(), validator))
+                })
+                .collect::<Result<BTreeMap<_, _>, Error>>()
+        };
+
+        Ok(Self {
+            tags: compile(&schemas.tags)?,
+            schema_refs: compile(&schemas.schema_refs)?,
+        })
+    }
+
+    /// Validate the JSON content of an entry of customXml.json, the pointers start at the entry's `index`
+    ///
+    /// The root is checked against the schemas of its tag and of its item props' schema references, each element holding
+    /// JSON against the schema of its own tag
+    pub fn validate(&self, index: usize, custom_xml_file: &CustomXmlFile) -> Vec<SchemaViolation> {
+        let custom_xml_info = &custom_xml_file.custom_xml_info;
+        let mut violations = Vec::new();
+
+        if custom_xml_info.format == CustomXmlFormat::Json {
+            let schema_refs = custom_xml_file
+                .item_props
+                .iter()
+                .flat_map(|item_props| &item_props.schema_refs)
+                .filter_map(|schema_ref| self.schema_refs.get(schema_ref));
+            let pointer = format!("/{}/custom_xml_info/json_content", index);
+            for validator in self
+                .tags
+                .get(&custom_xml_info.tag)
+                .into_iter()
+                .chain(schema_refs)
+            {
+                push_violations(
+                    validator,
+                    &custom_xml_info.json_content,
+                    &pointer,
+                    &mut violations,
+                );
+            }
+        }
+
+        for (element_index, element) in custom_xml_info.elements.iter().enumerate() {
+            if let Some(validator) = self.tags.get(get_element_tag(&element.path)) {
+                let pointer = format!(
+                    "/{}/custom_xml_info/elements/{}/json_content",
+                    index, element_index
+                );
+                push_violations(validator, &element.json_content, &pointer, &mut violations);
+            }
+        }
+
+        violations
+    }
+}
+
+fn compile_schema(path: &str, schema: &Value) -> Result<Validator, Error> {
+    jsonschema::validator_for(schema)
+        .map_err(|e| Error::invalid_package(path, format!("Invalid JSON Schema: {}", e)))
+}
+
+fn push_violations(
+    validator: &Validator,
+    json_content: &Value,
+    pointer: &str,
+    violations: &mut Vec<SchemaViolation>,
+) {
+    for error in validator.iter_errors(json_content) {
+        let violation = SchemaViolation {
+            pointer: format!("{}{}", pointer, error.instance_path().as_str()),
+            message: error.to_string(),
+        };
+        // A value can fail the same way against the tag's and a schema reference's schema
+        if !violations.contains(&violation) {
+            violations.push(violation);
+        }
+    }
+}
+
+/// The tag of an element from its path, e.g. `ns0:item` for `/ns0:root/ns0:item[2]`
+fn get_element_tag(path: &str) -> &str {
+    let last_step = path.rsplit('/').next().unwrap_or(path);
+    last_step.split('[').next().unwrap_or(last_step)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{
+        analyze_custom_xml::{item_props::CustomXmlProps, main::parse_custom_xml_content},
+        types::FileInfo,
+    };
+    use serde_json::json;
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn get_custom_xml_file(content: &str, schema_refs: &[&str]) -> CustomXmlFile {
+        CustomXmlFile {
+            file_info: FileInfo {
+                file_name_with_extension: "item1.xml".to_string(),
+                full_file_path: "customXml/item1.xml".to_string(),
+                file_size_in_kb: 0.0,
+            },
+            item_props: Some(CustomXmlProps {
+                item_id: "{A}".to_string(),
+                schema_refs: schema_refs.iter().map(|s| s.to_string()).collect(),
+            }),
+            custom_xml_info: parse_custom_xml_content(content).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_validate_custom_xml_file() {
+        let temp_dir = tempdir().unwrap();
+        let root_folder = temp_dir.path().to_str().unwrap();
+        fs::write(
+            temp_dir.path().join("invoice.json"),
+            r#"{"type": "object", "required": ["total"], "properties": {"total": {"type": "number"}}}"#,
+        )
+        .unwrap();
+        let schemas = CustomXmlSchemas {
+            tags: BTreeMap::from([
+                ("invoice".to_string(), json!("invoice.json")),
+                ("config".to_string(), json!({"type": "object"})),
+            ]),
+            schema_refs: BTreeMap::from([(
+                "urn:lines".to_string(),
+                json!({"properties": {"lines": {"type": "array", "items": {"type": "string"}}}}),
+            )]),
+        };
+        let validator = CustomXmlSchemaValidator::new(root_folder, &schemas).unwrap();
+
+        let valid = get_custom_xml_file(
+            r#"<invoice>{"total": 1, "lines": ["a"]}</invoice>"#,
+            &["urn:lines"],
+        );
+        assert_eq!(validator.validate(0, &valid), Vec::new());
+
+        let invalid = get_custom_xml_file(
+            r#"<invoice>{"total": "1", "lines": ["a", 2]}</invoice>"#,
+            &["urn:lines"],
+        );
+        let pointers: Vec<String> = validator
+            .validate(3, &invalid)
+            .into_iter()
+            .map(|violation| violation.pointer)
+            .collect();
+        assert_eq!(
+            pointers,
+            vec![
+                "/3/custom_xml_info/json_content/total",
+                "/3/custom_xml_info/json_content/lines/1"
+            ]
+        );
+
+        // Elements are checked by their own tag
+        let elements = get_custom_xml_file("<root><config>[]</config><data>[]</data></root>", &[]);
+        let violations = validator.validate(0, &elements);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(
+            violations[0].pointer,
+            "/0/custom_xml_info/elements/0/json_content"
+        );
+
+        // A schema that can't be compiled is reported
+        let schemas = CustomXmlSchemas {
+            tags: BTreeMap::from([("root".to_string(), json!({"type": 1}))]),
+            ..Default::default()
+        };
+        assert!(CustomXmlSchemaValidator::new(root_folder, &schemas).is_err());
+    }
+
+    #[test]
+    fn test_register_custom_xml_schema() {
+        let temp_dir = tempdir().unwrap();
+        let root_folder = temp_dir.path().to_str().unwrap();
+        let target = SchemaTarget::Tag("root".to_string());
+
+        register_custom_xml_schema(root_folder, &target, json!({"type": "array"})).unwrap();
+        register_custom_xml_schema(
+            root_folder,
+            &SchemaTarget::SchemaRef("urn:root".to_string()),
+            json!(true),
+        )
+        .unwrap();
+        // Replaced by the newer schema of the same tag
+        register_custom_xml_schema(root_folder, &target, json!({"type": "object"})).unwrap();
+        // Neither a missing schema file nor an invalid schema is registered
+        assert!(register_custom_xml_schema(root_folder, &target, json!("missing.json")).is_err());
+        assert!(register_custom_xml_schema(root_folder, &target, json!({"type": 1})).is_err());
+
+        assert_eq!(
+            read_custom_xml_schemas(root_folder).unwrap(),
+            CustomXmlSchemas {
+                tags: BTreeMap::from([("root".to_string(), json!({"type": "object"}))]),
+                schema_refs: BTreeMap::from([("urn:root".to_string(), json!(true))]),
+            }
+        );
+    }
+}
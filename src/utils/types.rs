@@ -1,16 +1,16 @@
-use crate::utils::{
-    files::{read_struct_from_json, write_struct_to_json},
-    print_utils::print_error_with_panic,
-};
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 
+use crate::utils::error::Error;
+
 pub const EXTRACTED_FOLDER_NAME: &str = "extracted";
 pub const SUMMARY_FILE_NAME: &str = "summary.json";
 /// The name of the analyzed custom XML file
 pub const CUSTOM_XML_FILE_NAME: &str = "customXml.json";
-/// The path to the preference file that store user's last used params
-const PREFERENCE_FILE_PATH: &str = "preference.json";
+/// The name of the JSON Schemas registered for the custom XML data
+pub const CUSTOM_XML_SCHEMAS_FILE_NAME: &str = "customXmlSchemas.json";
+/// The name of the manifest of the original archive entries
+pub const MANIFEST_FILE_NAME: &str = "manifest.json";
 
 /// The path to the fixture folder
 #[cfg(test)]
@@ -18,6 +18,7 @@ pub const FIXTURE_FOLDER_PATH: &str = ".local";
 
 // * ---
 /// Info about the file path
+#[derive(Debug, Clone)]
 pub struct FilePathInfo {
     /// Name of the file without extension
     pub file_name: String,
@@ -30,16 +31,18 @@ pub struct FilePathInfo {
 }
 
 impl FilePathInfo {
-    pub fn new(full_file_path: String) -> Self {
+    pub fn new(full_file_path: String) -> Result<Self, Error> {
         // https://stackoverflow.com/questions/73845791/how-to-remove-path-and-get-the-filename-in-rust
         let path = Path::new(&full_file_path);
 
         let file_name_with_extension = match path.file_name() {
-            Some(file_name) => file_name.to_str().unwrap(),
-            None => print_error_with_panic(&format!(
-                "Path doesn't contain a file name {}",
-                full_file_path
-            )),
+            Some(file_name) => file_name.to_string_lossy().to_string(),
+            None => {
+                return Err(Error::invalid_package(
+                    &full_file_path,
+                    "Path doesn't contain a file name",
+                ));
+            }
         };
 
         let file_extension = path
@@ -47,33 +50,39 @@ impl FilePathInfo {
             .map(|extension| extension.to_string_lossy().to_string());
         let file_size = match path.metadata() {
             Ok(metadata) => metadata.len(),
-            Err(e) => print_error_with_panic(&format!("Failed to get file size: {}", e)),
+            Err(e) => return Err(Error::io(&full_file_path, e)),
         };
 
         let file_name = match file_name_with_extension.split('.').next() {
             Some(file_name) => file_name.to_string(),
-            None => print_error_with_panic(&format!(
-                "File name doesn't have a extension: {}",
-                full_file_path
-            )),
+            None => {
+                return Err(Error::invalid_package(
+                    &full_file_path,
+                    "File name doesn't have a extension",
+                ));
+            }
         };
 
         let file_path_without_file_name = match path.parent() {
-            Some(parent) => parent.to_str().unwrap().to_string(),
-            None => print_error_with_panic(&format!(
-                "File path doesn't have a parent: {}",
-                full_file_path
-            )),
+            // A bare file name has an empty parent, which means the current folder
+            Some(parent) if parent.as_os_str().is_empty() => ".".to_string(),
+            Some(parent) => parent.to_string_lossy().to_string(),
+            None => {
+                return Err(Error::invalid_package(
+                    &full_file_path,
+                    "File path doesn't have a parent",
+                ));
+            }
         };
 
-        Self {
+        Ok(Self {
             file_name,
             file_extension,
-            file_name_with_extension: file_name_with_extension.to_string(),
+            file_name_with_extension,
             file_path: file_path_without_file_name,
             full_file_path,
             file_size,
-        }
+        })
     }
 
     pub fn print_info(&self) {
@@ -91,7 +100,7 @@ impl FilePathInfo {
                 .bright_cyan()
         );
 
-        let extension = self.file_extension.as_ref().unwrap();
+        let extension = self.file_extension.as_deref().unwrap_or("N/A");
         let file_size_kb = self.file_size as f64 / 1024.0;
         let file_size_mb = file_size_kb / 1024.0;
         let size_display = if file_size_mb >= 1.0 {
@@ -120,6 +129,7 @@ impl FilePathInfo {
 
 // * ---
 
+#[derive(Debug, Clone)]
 pub struct ZipFolder {
     /// The root folder of the zip folder
     pub root_folder: String,
@@ -127,7 +137,7 @@ pub struct ZipFolder {
     pub extracted_folder: String,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FileInfo {
     /// Name of the file with extension
     pub file_name_with_extension: String,
@@ -136,66 +146,3 @@ pub struct FileInfo {
     /// File size in KB
     pub file_size_in_kb: f64,
 }
-
-/// The user preference that stores the last used feature and file path
-///
-/// ! Note that this will automatically save to file when the last used feature, file path or folder path is changed
-#[derive(Deserialize, Serialize, Debug, Clone)]
-pub struct UserPreference {
-    pub last_used_feature: String,
-    pub last_used_file_path: String,
-    /// Folder containing the extracted files
-    pub last_used_extracted_folder_path: String,
-    /// The path to the output file that will be created
-    pub last_used_output_file_path: String,
-    /// The path to the root folder that contains the extracted and other files such as customXml.json and summary.json
-    pub last_used_root_folder_path: String,
-}
-
-impl UserPreference {
-    pub fn new() -> Self {
-        match read_struct_from_json::<UserPreference>(PREFERENCE_FILE_PATH) {
-            Ok(user_preference) => user_preference,
-            Err(_) => {
-                let user_preference = Self {
-                    last_used_feature: "N/A".to_string(),
-                    last_used_file_path: "N/A".to_string(),
-                    last_used_extracted_folder_path: "N/A".to_string(),
-                    last_used_output_file_path: "N/A".to_string(),
-                    last_used_root_folder_path: "N/A".to_string(),
-                };
-                user_preference.save_to_file();
-                user_preference
-            }
-        }
-    }
-
-    pub fn save_to_file(&self) {
-        let _ = write_struct_to_json(self, PREFERENCE_FILE_PATH);
-    }
-
-    pub fn save_last_used_file_path(&mut self, file_path: String) {
-        self.last_used_file_path = file_path;
-        self.save_to_file()
-    }
-
-    pub fn save_last_used_folder_path(&mut self, folder_path: String) {
-        self.last_used_extracted_folder_path = folder_path;
-        self.save_to_file()
-    }
-
-    pub fn save_last_used_feature(&mut self, feature: String) {
-        self.last_used_feature = feature;
-        self.save_to_file()
-    }
-
-    pub fn save_last_used_output_file_path(&mut self, last_used_output_file_path: String) {
-        self.last_used_output_file_path = last_used_output_file_path;
-        self.save_to_file()
-    }
-
-    pub fn save_last_used_root_folder_path(&mut self, root_folder_path: String) {
-        self.last_used_root_folder_path = root_folder_path;
-        self.save_to_file()
-    }
-}
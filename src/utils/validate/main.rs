@@ -0,0 +1,350 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use crate::utils::{
+    content_types::{ContentTypes, PackageType},
+    error::Error,
+    files::get_part_names_in_folder,
+    ooxml_package::OoxmlPackage,
+    relationships::{
+        Relationship, get_source_part_name, is_rels_part, parse_relationships,
+        resolve_relationship_target,
+    },
+    zip_utils::main::{CONTENT_TYPES_PART_NAME, PACKAGE_RELS_PART_NAME},
+};
+
+/// A problem found in the structure of a package
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ValidationIssue {
+    /// `[Content_Types].xml` is missing, no part has a content type
+    MissingContentTypes,
+    /// Neither an `<Override>` nor a `<Default>` of `[Content_Types].xml` matches the part
+    MissingContentType { part_name: String },
+    /// An internal relationship points to a part that doesn't exist
+    MissingRelationshipTarget {
+        rels_part_name: String,
+        id: String,
+        target: String,
+    },
+    /// The same `Id` is used by more than one relationship of a `.rels` part
+    DuplicateRelationshipId { rels_part_name: String, id: String },
+    /// No relationship points to the part
+    OrphanPart { part_name: String },
+    /// The main part is not declared, or it's declared but doesn't exist
+    MissingMainPart { part_name: Option<String> },
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingContentTypes => write!(f, "{} is missing", CONTENT_TYPES_PART_NAME),
+            Self::MissingContentType { part_name } => {
+                write!(f, "{} has no content type", part_name)
+            }
+            Self::MissingRelationshipTarget {
+                rels_part_name,
+                id,
+                target,
+            } => write!(
+                f,
+                "{} relationship {} points to the missing part {}",
+                rels_part_name, id, target
+            ),
+            Self::DuplicateRelationshipId { rels_part_name, id } => {
+                write!(
+                    f,
+                    "{} has more than one relationship {}",
+                    rels_part_name, id
+                )
+            }
+            Self::OrphanPart { part_name } => {
+                write!(f, "{} is not referenced by any relationship", part_name)
+            }
+            Self::MissingMainPart {
+                part_name: Some(part_name),
+            } => write!(f, "The main part {} is missing", part_name),
+            Self::MissingMainPart { part_name: None } => {
+                write!(f, "No main part is declared in {}", PACKAGE_RELS_PART_NAME)
+            }
+        }
+    }
+}
+
+/// The result of validating a package
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationReport {
+    /// The extracted folder or the packed file that was validated
+    pub path: PathBuf,
+    pub package_type: PackageType,
+    pub issues: Vec<ValidationIssue>,
+}
+
+impl ValidationReport {
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// Validate the structure of an extracted folder, see [`validate_archive`] for the checks
+pub fn validate_folder(extracted_folder: &str) -> Result<ValidationReport, Error> {
+    let folder_path = Path::new(extracted_folder);
+    if !folder_path.is_dir() {
+        return Err(Error::invalid_package(
+            folder_path,
+            "The extracted folder does not exist",
+        ));
+    }
+
+    let part_names = get_part_names_in_folder(folder_path)?;
+
+    validate_parts(folder_path, &part_names, &mut |part_name| {
+        let part_path = folder_path.join(part_name);
+        fs::read_to_string(&part_path).map_err(|e| Error::io(&part_path, e))
+    })
+}
+
+/// Validate the structure of a package straight from the archive
+///
+/// `[Content_Types].xml` and every `.rels` part are parsed to find parts without a content type, relationships pointing to
+/// missing parts, duplicate relationship ids, parts that nothing references and a missing main part
+pub fn validate_archive(ooxml_package: &OoxmlPackage) -> Result<ValidationReport, Error> {
+    let part_names: Vec<String> = ooxml_package.part_names().map(str::to_string).collect();
+
+    validate_parts(&ooxml_package.path, &part_names, &mut |part_name| {
+        ooxml_package.read_part_to_string(part_name)
+    })
+}
+
+/// Run all the checks on the parts of a package, `read_part` is only called for `[Content_Types].xml` and `.rels` parts
+fn validate_parts(
+    path: &Path,
+    part_names: &[String],
+    read_part: &mut dyn FnMut(&str) -> Result<String, Error>,
+) -> Result<ValidationReport, Error> {
+    let mut issues: Vec<ValidationIssue> = Vec::new();
+
+    // Part names are compared case-insensitively in a package
+    let existing_parts: HashSet<String> = part_names
+        .iter()
+        .map(|part_name| part_name.to_lowercase())
+        .collect();
+
+    // * Content types
+    let content_types = if existing_parts.contains(&CONTENT_TYPES_PART_NAME.to_lowercase()) {
+        let content_types = ContentTypes::parse(&read_part(CONTENT_TYPES_PART_NAME)?)
+            .map_err(|e| Error::invalid_package(path.join(CONTENT_TYPES_PART_NAME), e))?;
+        for part_name in part_names {
+            if part_name != CONTENT_TYPES_PART_NAME
+                && content_types.get_content_type(part_name).is_none()
+            {
+                issues.push(ValidationIssue::MissingContentType {
+                    part_name: part_name.clone(),
+                });
+            }
+        }
+        content_types
+    } else {
+        issues.push(ValidationIssue::MissingContentTypes);
+        ContentTypes::default()
+    };
+
+    // * Relationships
+    let mut relationships_by_part: Vec<(&str, String, Vec<Relationship>)> = Vec::new();
+    for part_name in part_names {
+        if let Some(source_part_name) = get_source_part_name(part_name) {
+            let relationships = parse_relationships(&read_part(part_name)?)
+                .map_err(|e| Error::invalid_package(path.join(part_name), e))?;
+            relationships_by_part.push((part_name, source_part_name, relationships));
+        }
+    }
+
+    // The main part is the target of the package `officeDocument` relationship, or the one declared in the content types
+    let main_part_name = relationships_by_part
+        .iter()
+        .find(|(rels_part_name, _, _)| *rels_part_name == PACKAGE_RELS_PART_NAME)
+        .and_then(|(_, source_part_name, relationships)| {
+            relationships
+                .iter()
+                .find(|relationship| relationship.is_office_document() && !relationship.is_external)
+                .map(|relationship| {
+                    resolve_relationship_target(source_part_name, &relationship.target)
+                })
+        })
+        .or_else(|| content_types.main_part_name().map(str::to_string));
+    let is_main_part_missing = match &main_part_name {
+        Some(main_part_name) => !existing_parts.contains(&main_part_name.to_lowercase()),
+        None => true,
+    };
+    if is_main_part_missing {
+        issues.push(ValidationIssue::MissingMainPart {
+            part_name: main_part_name.clone(),
+        });
+    }
+
+    let mut referenced_parts: HashSet<String> = HashSet::new();
+    for (rels_part_name, source_part_name, relationships) in &relationships_by_part {
+        let mut id_counts: HashMap<&str, usize> = HashMap::new();
+        for relationship in relationships {
+            let id_count = id_counts.entry(relationship.id.as_str()).or_default();
+            *id_count += 1;
+            if *id_count == 2 {
+                issues.push(ValidationIssue::DuplicateRelationshipId {
+                    rels_part_name: rels_part_name.to_string(),
+                    id: relationship.id.clone(),
+                });
+            }
+
+            if relationship.is_external {
+                continue;
+            }
+
+            let target = resolve_relationship_target(source_part_name, &relationship.target);
+            let target_key = target.to_lowercase();
+            // The missing main part is already reported
+            let is_main_part = main_part_name
+                .as_ref()
+                .is_some_and(|main_part_name| main_part_name.to_lowercase() == target_key);
+            if !existing_parts.contains(&target_key) && !is_main_part {
+                issues.push(ValidationIssue::MissingRelationshipTarget {
+                    rels_part_name: rels_part_name.to_string(),
+                    id: relationship.id.clone(),
+                    target: target.clone(),
+                });
+            }
+            referenced_parts.insert(target_key);
+        }
+    }
+
+    // * Orphan parts, the content types and the relationships themselves are never referenced
+    for part_name in part_names {
+        if part_name != CONTENT_TYPES_PART_NAME
+            && !is_rels_part(part_name)
+            && !referenced_parts.contains(&part_name.to_lowercase())
+        {
+            issues.push(ValidationIssue::OrphanPart {
+                part_name: part_name.clone(),
+            });
+        }
+    }
+
+    Ok(ValidationReport {
+        path: path.to_path_buf(),
+        package_type: content_types.package_type(),
+        issues,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::ooxml_package::build_test_package;
+
+    const CONTENT_TYPES: &str = r#"<Types>
+    <Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>
+    <Default Extension="xml" ContentType="application/xml"/>
+    <Override PartName="/word/document.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml"/>
+</Types>"#;
+    const PACKAGE_RELS: &str = r#"<Relationships>
+    <Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="word/document.xml"/>
+</Relationships>"#;
+
+    #[test]
+    fn test_validate_archive_valid() {
+        let package = build_test_package(
+            "test.docx",
+            &[
+                ("[Content_Types].xml", CONTENT_TYPES),
+                ("_rels/.rels", PACKAGE_RELS),
+                (
+                    "word/_rels/document.xml.rels",
+                    r#"<Relationships>
+    <Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/customXml" Target="../customXml/item1.xml"/>
+    <Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/hyperlink" Target="https://example.com" TargetMode="External"/>
+</Relationships>"#,
+                ),
+                ("word/document.xml", "<w:document/>"),
+                ("customXml/item1.xml", "<root/>"),
+            ],
+        );
+
+        let report = validate_archive(&package).unwrap();
+
+        assert_eq!(report.package_type, PackageType::Word);
+        assert!(report.is_valid(), "{:?}", report.issues);
+    }
+
+    #[test]
+    fn test_validate_archive_issues() {
+        let package = build_test_package(
+            "test.docx",
+            &[
+                ("[Content_Types].xml", CONTENT_TYPES),
+                ("_rels/.rels", PACKAGE_RELS),
+                (
+                    "customXml/_rels/item1.xml.rels",
+                    r#"<Relationships>
+    <Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/customXmlProps" Target="itemProps1.xml"/>
+    <Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/customXmlProps" Target="itemProps2.xml"/>
+</Relationships>"#,
+                ),
+                ("customXml/item1.xml", "<root/>"),
+                ("customXml/itemProps1.xml", "<ds:datastoreItem/>"),
+                ("word/media/image1.png", "png"),
+            ],
+        );
+
+        let report = validate_archive(&package).unwrap();
+
+        assert_eq!(
+            report.issues,
+            vec![
+                ValidationIssue::MissingContentType {
+                    part_name: "word/media/image1.png".to_string()
+                },
+                ValidationIssue::MissingMainPart {
+                    part_name: Some("word/document.xml".to_string())
+                },
+                ValidationIssue::DuplicateRelationshipId {
+                    rels_part_name: "customXml/_rels/item1.xml.rels".to_string(),
+                    id: "rId1".to_string()
+                },
+                ValidationIssue::MissingRelationshipTarget {
+                    rels_part_name: "customXml/_rels/item1.xml.rels".to_string(),
+                    id: "rId1".to_string(),
+                    target: "customXml/itemProps2.xml".to_string()
+                },
+                ValidationIssue::OrphanPart {
+                    part_name: "customXml/item1.xml".to_string()
+                },
+                ValidationIssue::OrphanPart {
+                    part_name: "word/media/image1.png".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_folder() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        for (name, content) in [
+            ("[Content_Types].xml", CONTENT_TYPES),
+            ("_rels/.rels", PACKAGE_RELS),
+            ("word/document.xml", "<w:document/>"),
+        ] {
+            let path = temp_dir.path().join(name);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+
+        let report = validate_folder(&temp_dir.path().to_string_lossy()).unwrap();
+        assert!(report.is_valid(), "{:?}", report.issues);
+
+        fs::remove_file(temp_dir.path().join("[Content_Types].xml")).unwrap();
+        let report = validate_folder(&temp_dir.path().to_string_lossy()).unwrap();
+        assert_eq!(report.issues, vec![ValidationIssue::MissingContentTypes]);
+    }
+}
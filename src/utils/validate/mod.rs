@@ -0,0 +1,2 @@
+pub mod main;
+pub mod well_formed;
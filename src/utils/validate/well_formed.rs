@@ -0,0 +1,153 @@
+use quick_xml::{Reader, events::Event};
+use std::path::Path;
+
+use crate::utils::{error::Error, xml::resolve_entity};
+
+/// Whether the part is parsed by [`check_xml_well_formed`], i.e. a `.xml` or `.rels` part
+pub fn is_xml_part(part_name: &str) -> bool {
+    let part_name = part_name.to_lowercase();
+    part_name.ends_with(".xml") || part_name.ends_with(".rels")
+}
+
+/// Make sure the content is well-formed XML, `path` is only used to describe the part in the error
+///
+/// The error is [`Error::XmlSyntax`] with the 1-based line and column of the first problem
+pub fn check_xml_well_formed(path: impl AsRef<Path>, data: &[u8]) -> Result<(), Error> {
+    let to_error = |position: usize, reason: String| {
+        let (line, column) = get_line_and_column(data, position);
+        Error::xml_syntax(&path, line, column, reason)
+    };
+
+    let content = std::str::from_utf8(data)
+        .map_err(|e| to_error(e.valid_up_to(), "Invalid UTF-8".to_string()))?;
+    // The byte order mark isn't part of the document
+    let bom_length = if content.starts_with('\u{feff}') {
+        3
+    } else {
+        0
+    };
+
+    let mut reader = Reader::from_str(&content[bom_length..]);
+    let mut depth: usize = 0;
+    let mut has_root = false;
+    loop {
+        let event_position = bom_length + reader.buffer_position() as usize;
+        let event = reader
+            .read_event()
+            .map_err(|e| to_error(bom_length + reader.error_position() as usize, e.to_string()))?;
+
+        match event {
+            Event::Start(ref element) | Event::Empty(ref element) => {
+                if depth == 0 && has_root {
+                    return Err(to_error(
+                        event_position,
+                        "More than one root element".to_string(),
+                    ));
+                }
+                has_root = true;
+
+                for attribute in element.attributes() {
+                    attribute
+                        .map_err(|e| e.to_string())
+                        .and_then(|attribute| {
+                            attribute
+                                .unescape_value()
+                                .map(|_| ())
+                                .map_err(|e| e.to_string())
+                        })
+                        .map_err(|reason| to_error(event_position, reason))?;
+                }
+
+                if matches!(event, Event::Start(_)) {
+                    depth += 1;
+                }
+            }
+            Event::End(_) => depth -= 1,
+            Event::GeneralRef(reference) if resolve_entity(&reference).is_none() => {
+                return Err(to_error(
+                    event_position,
+                    format!("Unknown entity &{};", String::from_utf8_lossy(&reference)),
+                ));
+            }
+            Event::Text(text) if depth == 0 && !text.iter().all(u8::is_ascii_whitespace) => {
+                return Err(to_error(
+                    event_position,
+                    "Text outside of the root element".to_string(),
+                ));
+            }
+            Event::Eof => {
+                if depth > 0 {
+                    return Err(to_error(data.len(), "Missing end tag".to_string()));
+                }
+                if !has_root {
+                    return Err(to_error(data.len(), "Missing root element".to_string()));
+                }
+                return Ok(());
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The 1-based line and column of a byte position, the column counts characters
+fn get_line_and_column(data: &[u8], position: usize) -> (usize, usize) {
+    let before = &data[..position.min(data.len())];
+    let line_start = before
+        .iter()
+        .rposition(|byte| *byte == b'\n')
+        .map(|index| index + 1)
+        .unwrap_or(0);
+    let line = before.iter().filter(|byte| **byte == b'\n').count() + 1;
+    let column = String::from_utf8_lossy(&before[line_start..])
+        .chars()
+        .count()
+        + 1;
+
+    (line, column)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_error_location(content: &str) -> (usize, usize) {
+        match check_xml_well_formed("document.xml", content.as_bytes()) {
+            Err(Error::XmlSyntax { line, column, .. }) => (line, column),
+            result => panic!("Expected an XML syntax error, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn test_check_xml_well_formed_valid() {
+        let content = "\u{feff}<?xml version=\"1.0\"?>\n<w:document a=\"&amp;&#x30;\">\n  <w:t>a &lt; b</w:t>\n  <w:br/>\n</w:document>\n";
+        assert!(check_xml_well_formed("document.xml", content.as_bytes()).is_ok());
+    }
+
+    #[test]
+    fn test_check_xml_well_formed_errors() {
+        // Mismatched end tag
+        assert_eq!(
+            get_error_location("<w:document>\n  <w:p>\n  </w:r>\n</w:document>"),
+            (3, 3)
+        );
+        // Unclosed element
+        assert_eq!(get_error_location("<w:document>\n  <w:p>"), (2, 8));
+        // Duplicate attribute
+        assert_eq!(
+            get_error_location("<root>\n<a b=\"1\" b=\"2\"/></root>"),
+            (2, 1)
+        );
+        // Unknown entity
+        assert_eq!(get_error_location("<root>&nbsp;</root>"), (1, 7));
+        assert_eq!(get_error_location(""), (1, 1));
+        assert_eq!(get_error_location("<a/><b/>"), (1, 5));
+    }
+
+    #[test]
+    fn test_is_xml_part() {
+        assert!(is_xml_part("word/document.xml"));
+        assert!(is_xml_part("_rels/.rels"));
+        assert!(is_xml_part("[Content_Types].XML"));
+        assert!(!is_xml_part("word/media/image1.png"));
+    }
+}
@@ -0,0 +1,468 @@
+use quick_xml::{
+    Reader,
+    escape::{escape, partial_escape},
+    events::{BytesRef, Event},
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, ops::Range};
+
+/// A node of an XML document in its JSON representation
+///
+/// Text is a plain JSON string and elements are objects with a `tag`, so a document maps to JSON without losing the
+/// element order, repeated children, mixed content or namespace prefixes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum XmlNode {
+    /// Unescaped text
+    Text(String),
+    Element(XmlElement),
+    CData {
+        cdata: String,
+    },
+    Comment {
+        comment: String,
+    },
+    ProcessingInstruction {
+        processing_instruction: String,
+    },
+}
+
+/// An XML element, the tag keeps its namespace prefix (`ns0:item`) and the `xmlns` declarations are attributes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct XmlElement {
+    pub tag: String,
+    /// Unescaped attribute values by their qualified name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub attributes: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<XmlNode>,
+}
+
+/// Where a node is in the text it was parsed from, as byte ranges
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct XmlSpan {
+    /// The whole node, for an element from the `<` of its start tag to the `>` of its end tag
+    pub range: Range<usize>,
+    /// The text between the start and end tags, `None` for an empty element (`<a/>`) and the other nodes
+    pub content: Option<Range<usize>>,
+    /// Where new attributes go, right after the tag or after the last attribute
+    pub attributes_end: usize,
+    pub attributes: Vec<XmlAttributeSpan>,
+    /// The spans of the child nodes, in the same order as [`XmlElement::children`]
+    pub children: Vec<XmlSpan>,
+}
+
+/// Where an attribute is in the text it was parsed from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XmlAttributeSpan {
+    pub name: String,
+    /// From the name to the closing quote
+    pub range: Range<usize>,
+    /// The raw value, without the quotes
+    pub value: Range<usize>,
+}
+
+/// A replacement of a byte range of the text an element was parsed from, see [`XmlSpan`]
+pub type XmlEdit = (Range<usize>, String);
+
+/// Apply the edits to the text they were made for, the edits must not overlap
+pub fn apply_xml_edits(source: &str, mut edits: Vec<XmlEdit>) -> String {
+    // Applying the edits from the end keeps the positions of the others valid
+    edits.sort_by_key(|(range, _)| (range.start, range.end));
+    let mut updated = source.to_string();
+    for (range, text) in edits.into_iter().rev() {
+        updated.replace_range(range, &text);
+    }
+
+    updated
+}
+
+impl XmlElement {
+    /// Parse the root element of an XML document, the nodes before and after it (declaration, comments, etc) are dropped
+    ///
+    /// Whitespace-only text spanning lines next to child elements is indentation and is dropped as well, any other text is
+    /// kept as is, so the space of mixed content like `<p><b>a</b> <i>b</i></p>` survives
+    pub fn parse(xml_content: &str) -> Result<Self, String> {
+        Self::parse_with_spans(xml_content).map(|(root, _)| root)
+    }
+
+    /// Same as [`XmlElement::parse`], along with where the root element and its descendants are in `xml_content`
+    pub fn parse_with_spans(xml_content: &str) -> Result<(Self, XmlSpan), String> {
+        // The reader skips the byte order mark, skip it here instead so the positions stay relative to `xml_content`
+        let body = xml_content.trim_start_matches('\u{feff}');
+        let base = xml_content.len() - body.len();
+        let mut reader = Reader::from_str(body);
+        // The elements that are still open, the root element is the first one
+        let mut stack: Vec<(XmlElement, XmlSpan)> = Vec::new();
+
+        loop {
+            let event_start = base + reader.buffer_position() as usize;
+            let event = reader.read_event().map_err(|e| e.to_string())?;
+            let event_end = base + reader.buffer_position() as usize;
+            let mut span = XmlSpan {
+                range: event_start..event_end,
+                ..Default::default()
+            };
+
+            let node = match event {
+                Event::Start(ref element) | Event::Empty(ref element) => {
+                    let name = element.name();
+                    span.attributes_end =
+                        get_offset(xml_content, name.as_ref())? + name.as_ref().len();
+
+                    let mut attributes = BTreeMap::new();
+                    for attribute in element.attributes() {
+                        let attribute = attribute.map_err(|e| e.to_string())?;
+                        let name = String::from_utf8_lossy(attribute.key.as_ref()).to_string();
+                        let value_start = get_offset(xml_content, &attribute.value)?;
+                        let value_end = value_start + attribute.value.len();
+                        // The closing quote is right after the value
+                        span.attributes_end = value_end + 1;
+                        span.attributes.push(XmlAttributeSpan {
+                            name: name.clone(),
+                            range: get_offset(xml_content, attribute.key.as_ref())?..value_end + 1,
+                            value: value_start..value_end,
+                        });
+
+                        let value = attribute.unescape_value().map_err(|e| e.to_string())?;
+                        attributes.insert(name, value.to_string());
+                    }
+
+                    let xml_element = XmlElement {
+                        tag: String::from_utf8_lossy(name.as_ref()).to_string(),
+                        attributes,
+                        children: Vec::new(),
+                    };
+                    if matches!(event, Event::Start(_)) {
+                        span.content = Some(event_end..event_end);
+                        stack.push((xml_element, span));
+                        continue;
+                    }
+                    XmlNode::Element(xml_element)
+                }
+                Event::End(_) => {
+                    let (mut xml_element, mut element_span) =
+                        stack.pop().ok_or("Unexpected end tag")?;
+                    remove_indentation(&mut xml_element, &mut element_span);
+                    element_span.range.end = event_end;
+                    if let Some(content) = &mut element_span.content {
+                        content.end = event_start;
+                    }
+                    span = element_span;
+                    XmlNode::Element(xml_element)
+                }
+                Event::Text(text) => {
+                    XmlNode::Text(text.decode().map_err(|e| e.to_string())?.to_string())
+                }
+                Event::GeneralRef(reference) => XmlNode::Text(
+                    resolve_entity(&reference)
+                        .ok_or_else(|| {
+                            format!("Unknown entity &{};", String::from_utf8_lossy(&reference))
+                        })?
+                        .to_string(),
+                ),
+                Event::CData(cdata) => XmlNode::CData {
+                    cdata: cdata.decode().map_err(|e| e.to_string())?.to_string(),
+                },
+                Event::Comment(comment) => XmlNode::Comment {
+                    comment: comment.decode().map_err(|e| e.to_string())?.to_string(),
+                },
+                Event::PI(processing_instruction) => XmlNode::ProcessingInstruction {
+                    processing_instruction: String::from_utf8_lossy(&processing_instruction)
+                        .to_string(),
+                },
+                Event::Eof => return Err("Missing root element".to_string()),
+                Event::Decl(_) | Event::DocType(_) => continue,
+            };
+
+            match stack.last_mut() {
+                Some((parent, parent_span)) => push_child(parent, parent_span, node, span),
+                // The root element is done, or this is a node outside of it
+                None => {
+                    if let XmlNode::Element(root) = node {
+                        return Ok((root, span));
+                    }
+                }
+            }
+        }
+    }
+
+    /// The tag without its namespace prefix, e.g. `item` for `ns0:item`
+    pub fn local_name(&self) -> &str {
+        get_local_name(&self.tag)
+    }
+
+    /// The value of the first attribute with the local name, whatever its prefix
+    pub fn get_attribute(&self, local_name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(name, _)| get_local_name(name) == local_name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// The child elements with the local name, whatever their prefix
+    pub fn get_child_elements<'a>(
+        &'a self,
+        local_name: &'a str,
+    ) -> impl Iterator<Item = &'a XmlElement> {
+        self.children.iter().filter_map(move |child| match child {
+            XmlNode::Element(element) if element.local_name() == local_name => Some(element),
+            _ => None,
+        })
+    }
+
+    /// The text and CDATA of the element and all its descendants, in document order
+    pub fn get_text(&self) -> String {
+        let mut text = String::new();
+        self.push_text(&mut text);
+        text
+    }
+
+    fn push_text(&self, text: &mut String) {
+        for child in &self.children {
+            match child {
+                XmlNode::Text(child_text) | XmlNode::CData { cdata: child_text } => {
+                    text.push_str(child_text)
+                }
+                XmlNode::Element(element) => element.push_text(text),
+                _ => {}
+            }
+        }
+    }
+
+    /// Write the element as XML, the text and attribute values are escaped
+    pub fn to_xml_string(&self) -> String {
+        let mut xml = String::new();
+        self.write_to(&mut xml);
+        xml
+    }
+
+    fn write_to(&self, xml: &mut String) {
+        xml.push('<');
+        xml.push_str(&self.tag);
+        for (key, value) in &self.attributes {
+            xml.push_str(&format!(" {}=\"{}\"", key, escape(value)));
+        }
+
+        if self.children.is_empty() {
+            xml.push_str("/>");
+            return;
+        }
+
+        xml.push('>');
+        for child in &self.children {
+            child.write_to(xml);
+        }
+        xml.push_str("</");
+        xml.push_str(&self.tag);
+        xml.push('>');
+    }
+}
+
+impl XmlNode {
+    /// Write the node as XML, the text and attribute values are escaped
+    pub fn to_xml_string(&self) -> String {
+        let mut xml = String::new();
+        self.write_to(&mut xml);
+        xml
+    }
+
+    fn write_to(&self, xml: &mut String) {
+        match self {
+            XmlNode::Text(text) => xml.push_str(&partial_escape(text)),
+            XmlNode::Element(element) => element.write_to(xml),
+            // `]]>` can't be inside a CDATA section, it's split across two sections
+            XmlNode::CData { cdata } => xml.push_str(&format!(
+                "<![CDATA[{}]]>",
+                cdata.replace("]]>", "]]]]><![CDATA[>")
+            )),
+            XmlNode::Comment { comment } => xml.push_str(&format!("<!--{}-->", comment)),
+            XmlNode::ProcessingInstruction {
+                processing_instruction,
+            } => xml.push_str(&format!("<?{}?>", processing_instruction)),
+        }
+    }
+}
+
+/// Append a child, text right after another text is merged into it
+fn push_child(parent: &mut XmlElement, parent_span: &mut XmlSpan, node: XmlNode, span: XmlSpan) {
+    if let XmlNode::Text(text) = &node
+        && let Some(XmlNode::Text(previous_text)) = parent.children.last_mut()
+        && let Some(previous_span) = parent_span.children.last_mut()
+    {
+        previous_text.push_str(text);
+        previous_span.range.end = span.range.end;
+        return;
+    }
+
+    parent.children.push(node);
+    parent_span.children.push(span);
+}
+
+/// Drop the indentation of an element that has child elements, i.e. its whitespace-only text with a line break
+///
+/// Whitespace on a single line is content, e.g. the space between two runs of mixed content
+fn remove_indentation(element: &mut XmlElement, span: &mut XmlSpan) {
+    let has_child_element = element
+        .children
+        .iter()
+        .any(|child| matches!(child, XmlNode::Element(_)));
+    if !has_child_element {
+        return;
+    }
+
+    let is_kept: Vec<bool> = element
+        .children
+        .iter()
+        .map(|child| match child {
+            XmlNode::Text(text) => !(text.trim().is_empty() && text.contains('\n')),
+            _ => true,
+        })
+        .collect();
+    let mut kept = is_kept.iter();
+    element.children.retain(|_| *kept.next().unwrap());
+    let mut kept = is_kept.iter();
+    span.children.retain(|_| *kept.next().unwrap());
+}
+
+/// A qualified name without its namespace prefix, e.g. `item` for `ns0:item`
+pub fn get_local_name(name: &str) -> &str {
+    name.rsplit_once(':')
+        .map_or(name, |(_, local_name)| local_name)
+}
+
+/// The position of a slice borrowed from `content`
+fn get_offset(content: &str, slice: &[u8]) -> Result<usize, String> {
+    (slice.as_ptr() as usize)
+        .checked_sub(content.as_ptr() as usize)
+        .filter(|offset| offset + slice.len() <= content.len())
+        .ok_or_else(|| "Failed to locate a node in the XML content".to_string())
+}
+
+/// Resolve a character reference (`&#x30;`) or one of the predefined entities (`&amp;`)
+///
+/// `None` for any other entity, they would need a DTD that OOXML parts never have
+pub fn resolve_entity(reference: &BytesRef) -> Option<char> {
+    if let Ok(Some(character)) = reference.resolve_char_ref() {
+        return Some(character);
+    }
+
+    match reference.decode().ok()?.as_ref() {
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "amp" => Some('&'),
+        "apos" => Some('\''),
+        "quot" => Some('"'),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_xml_element_parse() {
+        let content = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ns0:root xmlns:ns0="http://example.com/ns">
+    <ns0:item id="1">First &amp; <b>bold</b> text</ns0:item>
+    <ns0:item id='2'/>
+    <!-- note -->
+    <ns0:code><![CDATA[a < b]]></ns0:code>
+</ns0:root>"#;
+
+        let root = XmlElement::parse(content).unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&root).unwrap(),
+            json!({
+                "tag": "ns0:root",
+                "attributes": { "xmlns:ns0": "http://example.com/ns" },
+                "children": [
+                    {
+                        "tag": "ns0:item",
+                        "attributes": { "id": "1" },
+                        "children": ["First & ", { "tag": "b", "children": ["bold"] }, " text"]
+                    },
+                    { "tag": "ns0:item", "attributes": { "id": "2" } },
+                    { "comment": " note " },
+                    { "tag": "ns0:code", "children": [{ "cdata": "a < b" }] }
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn test_xml_element_round_trip() {
+        let content = r#"<root a="x &quot;y&quot;"><item>1 &lt; 2</item><item/><![CDATA[raw]]><?pi data?></root>"#;
+
+        let root = XmlElement::parse(content).unwrap();
+        let json = serde_json::to_string(&root).unwrap();
+        let from_json: XmlElement = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(from_json, root);
+        assert_eq!(from_json.to_xml_string(), content);
+    }
+
+    #[test]
+    fn test_xml_element_round_trip_mixed_content() {
+        let content = "<p>\n    <r><b>a</b> <i>b</i>\t<i>c</i></r>\n    <r> </r>\n</p>";
+
+        let root = XmlElement::parse(content).unwrap();
+        let json = serde_json::to_value(&root).unwrap();
+        let from_json: XmlElement = serde_json::from_value(json.clone()).unwrap();
+
+        // Only the indentation is dropped, the spaces between the runs are content
+        assert_eq!(
+            json["children"][0]["children"],
+            json!([
+                { "tag": "b", "children": ["a"] },
+                " ",
+                { "tag": "i", "children": ["b"] },
+                "\t",
+                { "tag": "i", "children": ["c"] }
+            ])
+        );
+        assert_eq!(
+            from_json.to_xml_string(),
+            "<p><r><b>a</b> <i>b</i>\t<i>c</i></r><r> </r></p>"
+        );
+    }
+
+    #[test]
+    fn test_xml_element_parse_err() {
+        assert!(XmlElement::parse("").is_err());
+        assert!(XmlElement::parse("<root><item></root>").is_err());
+        assert!(XmlElement::parse("<root>&nbsp;</root>").is_err());
+    }
+
+    #[test]
+    fn test_resolve_entity() {
+        assert_eq!(resolve_entity(&BytesRef::new("quot")), Some('"'));
+        assert_eq!(resolve_entity(&BytesRef::new("#x30")), Some('0'));
+        assert_eq!(resolve_entity(&BytesRef::new("#49")), Some('1'));
+        assert_eq!(resolve_entity(&BytesRef::new("nbsp")), None);
+        assert_eq!(resolve_entity(&BytesRef::new("#xZZ")), None);
+    }
+
+    #[test]
+    fn test_apply_xml_edits() {
+        let source = r#"<a b="1"><c/></a>"#;
+        let (_, span) = XmlElement::parse_with_spans(source).unwrap();
+        // In any order, an insertion next to a replacement included
+        let edits = vec![
+            (span.children[0].range.clone(), "<d/>".to_string()),
+            (
+                span.attributes_end..span.attributes_end,
+                r#" e="2""#.to_string(),
+            ),
+            (span.attributes[0].value.clone(), "10".to_string()),
+        ];
+
+        assert_eq!(
+            apply_xml_edits(source, edits),
+            r#"<a b="10" e="2"><d/></a>"#
+        );
+    }
+}
@@ -1,45 +1,30 @@
 use crate::utils::{
-    files::get_output_folder,
-    input_utils::get_path_from_input::{
-        get_file_path_from_input, get_folder_path_from_input_for_rezip,
-        get_output_file_path_from_input_for_rezip,
+    content_types::ContentTypes,
+    error::Error,
+    files::{get_output_folder, get_part_names_in_folder, write_struct_to_json},
+    types::{FilePathInfo, MANIFEST_FILE_NAME, ZipFolder},
+    validate::{
+        main::validate_folder,
+        well_formed::{check_xml_well_formed, is_xml_part},
+    },
+    zip_utils::manifest::{
+        ArchiveManifest, find_manifest_for_extracted_folder, parse_zip_date_time,
     },
-    print_utils::{print_error_with_panic, print_fn_progress},
-    types::{FilePathInfo, UserPreference, ZipFolder},
 };
-use colored::Colorize;
-use prompted::input;
+use serde::{Deserialize, Serialize};
 use std::{
-    fs::{create_dir_all, remove_dir_all},
+    fs::{self, File, create_dir_all, remove_dir_all},
+    io::{Cursor, Read, Seek, Write},
     path::Path,
 };
-use zip_extensions::{zip_create_from_directory, zip_extract};
+use zip::{CompressionMethod, DateTime, ZipArchive, ZipWriter, write::SimpleFileOptions};
 
 // * --- Unzip
-/// Extract the zip file into a new folder
-pub fn extract_zip_wrapper(user_preference: &mut UserPreference) {
-    println!("\n");
-    let fn_name = "Extract zip";
-    print_fn_progress(fn_name, "Extracting zip...");
-
-    let file_path_info = get_file_path_from_input(user_preference);
-    file_path_info.print_info();
-
-    let extract_result = extract_zip(&file_path_info);
-    if extract_result.is_err() {
-        print_error_with_panic(&format!(
-            "Failed to extract the zip: {}",
-            extract_result.err().unwrap()
-        ));
-    }
-
-    print_fn_progress(
-        fn_name,
-        "Zip extraction finished!\n".green().to_string().as_str(),
-    );
-}
-
-pub fn extract_zip(file_path_info: &FilePathInfo) -> Result<(), &'static str> {
+/// Extract the zip file into the `extracted` folder next to it
+///
+/// If the output root folder already exists, it's removed first when `overwrite` is `true`, otherwise this returns an error.
+/// The manifest of the original entries is written to the root folder so [`rezip_folder`] can restore them.
+pub fn extract_zip(file_path_info: &FilePathInfo, overwrite: bool) -> Result<(), Error> {
     let FilePathInfo { full_file_path, .. } = file_path_info;
 
     let ZipFolder {
@@ -51,127 +36,556 @@ pub fn extract_zip(file_path_info: &FilePathInfo) -> Result<(), &'static str> {
     let output_path = Path::new(&root_folder);
     if output_path.exists() {
         if !output_path.is_dir() {
-            print_error_with_panic(&format!(
-                "The output root folder is not a directory: {}",
-                output_path.to_string_lossy()
+            return Err(Error::invalid_package(
+                output_path,
+                "The output root folder is not a directory",
             ));
         }
 
-        println!(
-            "{}",
-            "The output root folder already exists... skipping folder creation".yellow()
-        );
-
-        let override_input = input!("\tOverride? (y/n - default: n): ");
-        if override_input.to_lowercase() == "y" {
-            match remove_dir_all(output_path) {
-                Ok(_) => println!(
-                    "{}",
-                    "Output folder removed successfully, creating new one...".blue()
-                ),
-                Err(e) => {
-                    print_error_with_panic(&format!("Failed to remove the output folder: {}", e))
-                }
-            }
-        } else {
-            println!("{}", "Operation cancelled".yellow());
-            return Ok(());
+        if !overwrite {
+            return Err(Error::invalid_package(
+                output_path,
+                "The output root folder already exists",
+            ));
         }
+
+        remove_dir_all(output_path).map_err(|e| Error::io(output_path, e))?;
     }
 
-    println!("Creating the output folder...");
+    let file = File::open(full_file_path).map_err(|e| Error::io(full_file_path, e))?;
+
+    let manifest = extract_zip_from_reader(file, &extracted_folder)?;
+
+    write_struct_to_json(
+        &manifest,
+        &format!("{}/{}", root_folder, MANIFEST_FILE_NAME),
+    )
+}
+
+/// Extract a zip archive from any reader into the given folder, creating the folder if needed
+///
+/// Returns the manifest of the original entries, see [`ArchiveManifest`]
+pub fn extract_zip_from_reader<R: Read + Seek>(
+    reader: R,
+    extracted_folder: &str,
+) -> Result<ArchiveManifest, Error> {
     // Create the output folder
-    let create_result = create_dir_all(&extracted_folder);
-    if create_result.is_err() {
-        print_error_with_panic(&format!(
-            "Failed to create the output folder {}: {}",
-            extracted_folder,
-            create_result.err().unwrap()
-        ));
-    }
+    create_dir_all(extracted_folder).map_err(|e| Error::io(extracted_folder, e))?;
 
-    // Extract the zip
-    match zip_extract(
-        &Path::new(full_file_path).to_path_buf(),
-        &Path::new(&extracted_folder).to_path_buf(),
-    ) {
-        Ok(_) => {
-            println!("{}", "Zip extracted successfully".green());
-            Ok(())
-        }
-        Err(e) => print_error_with_panic(&format!("Failed to extract the zip: {}", e)),
-    }
+    let mut archive = ZipArchive::new(reader).map_err(|e| Error::zip(extracted_folder, e))?;
+    let manifest = ArchiveManifest::from_archive(&mut archive, Path::new(extracted_folder))?;
+
+    archive
+        .extract(extracted_folder)
+        .map_err(|e| Error::zip(extracted_folder, e))?;
+
+    Ok(manifest)
 }
 
 // * --- Rezip
-/// Rezip an extracted folder into a Word file
-pub fn rezip_folder_wrapper(user_preference: &mut UserPreference) {
-    println!("\n");
-    let fn_name = "Rezip folder";
-    print_fn_progress(fn_name, "Rezipping folder...");
+/// The name of the content types part, Office expects it to be the first entry of the archive
+pub const CONTENT_TYPES_PART_NAME: &str = "[Content_Types].xml";
+/// The name of the package relationships part, written right after the content types
+pub const PACKAGE_RELS_PART_NAME: &str = "_rels/.rels";
 
-    let folder_path = get_folder_path_from_input_for_rezip(user_preference);
-    let output_file_path = get_output_file_path_from_input_for_rezip(user_preference);
-    println!("\tExtracted folder path: {}", folder_path);
-    println!("\tOutput file path: {}", output_file_path);
+/// How the parts are compressed when rezipping
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Compression {
+    /// Store the parts as is
+    Stored,
+    /// Deflate the parts with the given level (0-9), or the default level when `None`
+    Deflated(Option<i64>),
+}
 
-    if let Err(e) = rezip_folder(&folder_path, &output_file_path) {
-        print_error_with_panic(&e);
+impl Default for Compression {
+    fn default() -> Self {
+        Self::Deflated(None)
     }
+}
+
+/// Options for rezipping an extracted folder
+#[derive(Debug, Clone, Default)]
+pub struct RezipOptions {
+    /// Compression of every part, `None` keeps the original compression of the manifest entries and deflates the others
+    pub compression: Option<Compression>,
+    /// The manifest of the original archive, its entries are written first with their original compression and timestamp
+    pub manifest: Option<ArchiveManifest>,
+    /// Refuse to rezip when the folder has validation issues, see [`validate_folder`]
+    pub validate: bool,
+    /// Don't check that the `.xml` and `.rels` parts are well-formed, see [`check_xml_well_formed`]
+    pub skip_xml_check: bool,
+}
+
+impl RezipOptions {
+    /// The default options with the manifest recorded when the folder was extracted, if there is one
+    pub fn for_extracted_folder(extracted_folder: &str) -> Result<Self, Error> {
+        Ok(Self {
+            manifest: find_manifest_for_extracted_folder(extracted_folder)?,
+            ..Default::default()
+        })
+    }
+}
+
+/// Rezip an extracted folder into a Word file
+///
+/// The output is deterministic: `[Content_Types].xml` comes first, then `_rels/.rels`, then the other parts sorted by name,
+/// all with the same fixed timestamp. Rezipping the same folder twice gives identical files.
+///
+/// Every `.xml` and `.rels` part must be well-formed unless [`RezipOptions::skip_xml_check`] is set, nothing is written otherwise.
+///
+/// When a manifest is given, the parts listed in it are written first in the original order, compression and timestamp,
+/// so an unmodified folder is rezipped to an archive with the same entries as the original. An explicit
+/// [`RezipOptions::compression`] replaces the original compression, only the order and timestamps are kept then.
+/// Parts that were added since the extraction follow in the order above, and the removed ones are skipped.
+pub fn rezip_folder(
+    input_folder_path: &str,
+    output_file_path: &str,
+    options: &RezipOptions,
+) -> Result<(), Error> {
+    // Build the archive in memory first so the output file is left untouched when the folder is invalid
+    let bytes = rezip_folder_to_writer(input_folder_path, Cursor::new(Vec::new()), options)?;
 
-    print_fn_progress(fn_name, "Rezipping finished!".green().to_string().as_str());
+    fs::write(output_file_path, bytes.into_inner()).map_err(|e| Error::io(output_file_path, e))
 }
 
-pub fn rezip_folder(input_folder_path: &str, output_file_path: &str) -> Result<(), String> {
+/// Rezip an extracted folder into any writer, see [`rezip_folder`]
+pub fn rezip_folder_to_writer<W: Write + Seek>(
+    input_folder_path: &str,
+    writer: W,
+    options: &RezipOptions,
+) -> Result<W, Error> {
     let folder_path = Path::new(&input_folder_path);
     if !folder_path.is_dir() {
-        return Err(format!(
-            "The folder path is not a directory: {}",
-            folder_path.to_string_lossy()
+        return Err(Error::invalid_package(
+            folder_path,
+            "The folder path is not a directory",
         ));
     }
 
-    if let Err(validate_error) = validate_rezip_folder(folder_path) {
-        return Err(format!("Invalid rezip folder: {}", validate_error));
+    validate_rezip_folder(folder_path)?;
+    if options.validate {
+        let report = validate_folder(input_folder_path)?;
+        if !report.is_valid() {
+            return Err(Error::Validation {
+                path: report.path,
+                issues: report.issues,
+            });
+        }
+    }
+
+    let mut part_names = get_part_names_in_folder(folder_path)?;
+    part_names.sort_by(|a, b| {
+        get_part_name_rank(a)
+            .cmp(&get_part_name_rank(b))
+            .then_with(|| a.cmp(b))
+    });
+
+    // * Restore the original order and options of the parts in the manifest
+    let default_file_options = get_zip_file_options(options.compression.unwrap_or_default());
+    let mut parts: Vec<(&str, SimpleFileOptions)> = Vec::with_capacity(part_names.len());
+    if let Some(manifest) = &options.manifest {
+        for entry in &manifest.entries {
+            if !part_names.contains(&entry.name) {
+                continue;
+            }
+
+            let mut file_options =
+                get_zip_file_options(options.compression.unwrap_or(entry.compression));
+            if let Some(last_modified) =
+                entry.last_modified.as_deref().and_then(parse_zip_date_time)
+            {
+                file_options = file_options.last_modified_time(last_modified);
+            }
+            parts.push((&entry.name, file_options));
+        }
     }
 
-    println!(
-        "Creating the zip file from {} to {}...",
-        input_folder_path, output_file_path
-    );
+    for part_name in &part_names {
+        if !parts.iter().any(|(name, _)| name == part_name) {
+            parts.push((part_name, default_file_options));
+        }
+    }
 
-    match zip_create_from_directory(
-        &Path::new(&output_file_path).to_path_buf(),
-        &folder_path.to_path_buf(),
-    ) {
-        Ok(_) => {
-            println!("{}", "Zip file created successfully".green());
-            Ok(())
+    // * Read the parts and make sure the XML ones are well-formed before writing anything
+    let mut part_data: Vec<Vec<u8>> = Vec::with_capacity(parts.len());
+    for (part_name, _) in &parts {
+        let part_path = folder_path.join(part_name);
+        let data = fs::read(&part_path).map_err(|e| Error::io(&part_path, e))?;
+        if !options.skip_xml_check && is_xml_part(part_name) {
+            check_xml_well_formed(&part_path, &data)?;
         }
-        Err(e) => Err(format!(
-            "Failed to create the zip file from folder {}: {}",
-            input_folder_path, e
-        )),
+        part_data.push(data);
+    }
+
+    // * Write the parts
+    let mut zip_writer = ZipWriter::new(writer);
+    for ((part_name, file_options), data) in parts.into_iter().zip(part_data) {
+        let part_path = folder_path.join(part_name);
+        zip_writer
+            .start_file(part_name, file_options)
+            .map_err(|e| Error::zip(&part_path, e))?;
+        zip_writer
+            .write_all(&data)
+            .map_err(|e| Error::io(&part_path, e))?;
+    }
+
+    zip_writer.finish().map_err(|e| Error::zip(folder_path, e))
+}
+
+/// The options used for every entry written to an archive
+///
+/// The timestamp and permissions are fixed so the output doesn't depend on when or where it was written
+pub(crate) fn get_zip_file_options(compression: Compression) -> SimpleFileOptions {
+    let options = SimpleFileOptions::default()
+        .last_modified_time(DateTime::default())
+        .unix_permissions(0o644);
+
+    match compression {
+        Compression::Stored => options.compression_method(CompressionMethod::Stored),
+        Compression::Deflated(level) => options
+            .compression_method(CompressionMethod::Deflated)
+            .compression_level(level),
+    }
+}
+
+/// The rank of a part in the archive, parts with a lower rank are written first
+fn get_part_name_rank(part_name: &str) -> u8 {
+    match part_name {
+        CONTENT_TYPES_PART_NAME => 0,
+        PACKAGE_RELS_PART_NAME => 1,
+        _ => 2,
     }
 }
 
 /// Make sure the folder that the user trying to rezip with is valid
-/// This is quite simple, make sure it's a folder and contains `word` or `excel` folders and the `_rels` folder
-fn validate_rezip_folder(input_folder_path: &Path) -> Result<(), &'static str> {
+///
+/// The folder must contain `[Content_Types].xml` and the `_rels` folder. When the content types declare the main part of
+/// a Word, Excel or PowerPoint package, that part must exist as well.
+fn validate_rezip_folder(input_folder_path: &Path) -> Result<(), Error> {
     if !input_folder_path.is_dir() {
-        return Err("The extracted folder is not a directory");
+        return Err(Error::invalid_package(
+            input_folder_path,
+            "The extracted folder is not a directory",
+        ));
     }
 
-    let word_folder_path = input_folder_path.join("word");
-    let excel_folder_path = input_folder_path.join("excel");
-    if !word_folder_path.is_dir() && !excel_folder_path.is_dir() {
-        return Err("The extracted folder does not contain a word and excel folder");
-    }
+    let content_types = ContentTypes::read_from_folder(input_folder_path)?;
 
     let _rels_folder_path = input_folder_path.join("_rels");
     if !_rels_folder_path.is_dir() {
-        return Err("The extracted folder does not contain a _rels folder");
+        return Err(Error::invalid_package(
+            input_folder_path,
+            "The extracted folder does not contain a _rels folder",
+        ));
+    }
+
+    if let Some(main_part_name) = content_types.main_part_name()
+        && !input_folder_path.join(main_part_name).is_file()
+    {
+        return Err(Error::invalid_package(
+            input_folder_path,
+            format!(
+                "The extracted folder does not contain the main part {} of the {:?} package",
+                main_part_name,
+                content_types.package_type()
+            ),
+        ));
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Create a minimal extracted folder, the files are created in a shuffled order on purpose
+    fn create_extracted_folder() -> tempfile::TempDir {
+        let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let root = temp_dir.path();
+
+        for (name, content) in [
+            ("word/styles.xml", "<w:styles/>"),
+            ("docProps/app.xml", "<Properties/>"),
+            ("_rels/.rels", "<Relationships/>"),
+            ("word/document.xml", "<w:document/>"),
+            ("[Content_Types].xml", "<Types/>"),
+            ("customXml/item1.xml", "<root>{}</root>"),
+        ] {
+            let path = root.join(name);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+
+        temp_dir
+    }
+
+    fn rezip_to_bytes(folder: &Path, options: &RezipOptions) -> Vec<u8> {
+        rezip_folder_to_writer(&folder.to_string_lossy(), Cursor::new(Vec::new()), options)
+            .expect("Failed to rezip")
+            .into_inner()
+    }
+
+    #[test]
+    fn test_rezip_folder_entry_order() {
+        let folder = create_extracted_folder();
+        let bytes = rezip_to_bytes(folder.path(), &RezipOptions::default());
+
+        let mut archive = ZipArchive::new(Cursor::new(bytes)).unwrap();
+        let names: Vec<String> = (0..archive.len())
+            .map(|index| archive.by_index(index).unwrap().name().to_string())
+            .collect();
+
+        assert_eq!(
+            names,
+            vec![
+                "[Content_Types].xml",
+                "_rels/.rels",
+                "customXml/item1.xml",
+                "docProps/app.xml",
+                "word/document.xml",
+                "word/styles.xml",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rezip_folder_is_deterministic() {
+        let folder = create_extracted_folder();
+        let first = rezip_to_bytes(folder.path(), &RezipOptions::default());
+
+        // Touch a file so its modified time changes, the output should stay the same
+        let document_path = folder.path().join("word/document.xml");
+        fs::write(&document_path, "<w:document/>").unwrap();
+        let second = rezip_to_bytes(folder.path(), &RezipOptions::default());
+
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_rezip_folder_compression() {
+        let folder = create_extracted_folder();
+        let options = RezipOptions {
+            compression: Some(Compression::Stored),
+            ..Default::default()
+        };
+        let bytes = rezip_to_bytes(folder.path(), &options);
+
+        let mut archive = ZipArchive::new(Cursor::new(bytes)).unwrap();
+        for index in 0..archive.len() {
+            let entry = archive.by_index(index).unwrap();
+            assert_eq!(entry.compression(), CompressionMethod::Stored);
+            assert_eq!(entry.last_modified(), Some(DateTime::default()));
+        }
+    }
+
+    /// Extract an archive with deflated and stored entries, returns the temp dir and the extracted folder's manifest
+    fn extract_original_archive(last_modified: DateTime) -> (tempfile::TempDir, ArchiveManifest) {
+        let mut zip_writer = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content, compression) in [
+            (
+                "word/document.xml",
+                "<w:document/>",
+                CompressionMethod::Deflated,
+            ),
+            (
+                "[Content_Types].xml",
+                "<Types/>",
+                CompressionMethod::Deflated,
+            ),
+            (
+                "_rels/.rels",
+                "<Relationships/>",
+                CompressionMethod::Deflated,
+            ),
+            ("word/media/image1.png", "png", CompressionMethod::Stored),
+        ] {
+            let options = SimpleFileOptions::default()
+                .compression_method(compression)
+                .last_modified_time(last_modified);
+            zip_writer.start_file(name, options).unwrap();
+            zip_writer.write_all(content.as_bytes()).unwrap();
+        }
+        let original = zip_writer.finish().unwrap();
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let extracted_folder = temp_dir.path().join("extracted");
+        let extracted_folder = extracted_folder.to_string_lossy();
+        let manifest = extract_zip_from_reader(original, &extracted_folder).unwrap();
+
+        (temp_dir, manifest)
+    }
+
+    #[test]
+    fn test_rezip_folder_restores_manifest() {
+        let last_modified = DateTime::from_date_and_time(2024, 5, 17, 10, 30, 0).unwrap();
+        let (temp_dir, manifest) = extract_original_archive(last_modified);
+        let extracted_folder = temp_dir.path().join("extracted");
+
+        // A part added after the extraction goes after the original ones
+        fs::write(temp_dir.path().join("extracted/word/new.xml"), "<new/>").unwrap();
+
+        let options = RezipOptions {
+            manifest: Some(manifest),
+            ..Default::default()
+        };
+        let bytes = rezip_to_bytes(&extracted_folder, &options);
+
+        let mut archive = ZipArchive::new(Cursor::new(bytes)).unwrap();
+        let entries: Vec<(String, CompressionMethod, Option<DateTime>)> = (0..archive.len())
+            .map(|index| {
+                let entry = archive.by_index(index).unwrap();
+                (
+                    entry.name().to_string(),
+                    entry.compression(),
+                    entry.last_modified(),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            entries,
+            vec![
+                (
+                    "word/document.xml".to_string(),
+                    CompressionMethod::Deflated,
+                    Some(last_modified)
+                ),
+                (
+                    "[Content_Types].xml".to_string(),
+                    CompressionMethod::Deflated,
+                    Some(last_modified)
+                ),
+                (
+                    "_rels/.rels".to_string(),
+                    CompressionMethod::Deflated,
+                    Some(last_modified)
+                ),
+                (
+                    "word/media/image1.png".to_string(),
+                    CompressionMethod::Stored,
+                    Some(last_modified)
+                ),
+                (
+                    "word/new.xml".to_string(),
+                    CompressionMethod::Deflated,
+                    Some(DateTime::default())
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rezip_folder_explicit_compression_overrides_manifest() {
+        let last_modified = DateTime::from_date_and_time(2024, 5, 17, 10, 30, 0).unwrap();
+        let (temp_dir, manifest) = extract_original_archive(last_modified);
+        let options = RezipOptions {
+            compression: Some(Compression::Stored),
+            manifest: Some(manifest),
+            ..Default::default()
+        };
+        let bytes = rezip_to_bytes(&temp_dir.path().join("extracted"), &options);
+
+        // The order and timestamps still come from the manifest
+        let mut archive = ZipArchive::new(Cursor::new(bytes)).unwrap();
+        assert_eq!(archive.by_index(0).unwrap().name(), "word/document.xml");
+        for index in 0..archive.len() {
+            let entry = archive.by_index(index).unwrap();
+            assert_eq!(entry.compression(), CompressionMethod::Stored);
+            assert_eq!(entry.last_modified(), Some(last_modified));
+        }
+    }
+
+    #[test]
+    fn test_rezip_folder_err_on_validation_issues() {
+        let folder = create_extracted_folder();
+        let options = RezipOptions {
+            validate: true,
+            ..Default::default()
+        };
+        let result = rezip_folder_to_writer(
+            &folder.path().to_string_lossy(),
+            Cursor::new(Vec::new()),
+            &options,
+        );
+
+        // The content types are empty and nothing references the parts
+        assert!(matches!(result, Err(Error::Validation { .. })));
+    }
+
+    #[test]
+    fn test_rezip_folder_err_on_malformed_xml() {
+        let folder = create_extracted_folder();
+        fs::write(
+            folder.path().join("word/document.xml"),
+            "<w:document>\n  <w:body>\n</w:document>",
+        )
+        .unwrap();
+        let folder_path = folder.path().to_string_lossy();
+
+        let result = rezip_folder_to_writer(
+            &folder_path,
+            Cursor::new(Vec::new()),
+            &RezipOptions::default(),
+        );
+        match result {
+            Err(Error::XmlSyntax {
+                path, line, column, ..
+            }) => {
+                assert!(path.ends_with("word/document.xml"));
+                assert_eq!((line, column), (3, 1));
+            }
+            result => panic!("Expected an XML syntax error, got {:?}", result.err()),
+        }
+
+        let options = RezipOptions {
+            skip_xml_check: true,
+            ..Default::default()
+        };
+        assert!(rezip_folder_to_writer(&folder_path, Cursor::new(Vec::new()), &options).is_ok());
+    }
+
+    #[test]
+    fn test_rezip_folder_err_on_invalid_folder() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let result = rezip_folder_to_writer(
+            &temp_dir.path().to_string_lossy(),
+            Cursor::new(Vec::new()),
+            &RezipOptions::default(),
+        );
+
+        assert!(matches!(result, Err(Error::InvalidPackage { .. })));
+    }
+
+    #[test]
+    fn test_rezip_folder_presentation() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+        for (name, content) in [
+            (
+                "[Content_Types].xml",
+                r#"<Types><Override PartName="/ppt/presentation.xml" ContentType="application/vnd.openxmlformats-officedocument.presentationml.presentation.main+xml"/></Types>"#,
+            ),
+            ("_rels/.rels", "<Relationships/>"),
+        ] {
+            let path = root.join(name);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+
+        // The main part is missing
+        let result = rezip_folder_to_writer(
+            &root.to_string_lossy(),
+            Cursor::new(Vec::new()),
+            &RezipOptions::default(),
+        );
+        assert!(matches!(result, Err(Error::InvalidPackage { .. })));
+
+        fs::create_dir_all(root.join("ppt")).unwrap();
+        fs::write(root.join("ppt/presentation.xml"), "<p:presentation/>").unwrap();
+        let bytes = rezip_to_bytes(root, &RezipOptions::default());
+
+        let archive = ZipArchive::new(Cursor::new(bytes)).unwrap();
+        assert_eq!(archive.len(), 3);
+    }
+}
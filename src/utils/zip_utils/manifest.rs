@@ -0,0 +1,136 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    io::{Read, Seek},
+    path::Path,
+};
+use zip::{CompressionMethod, DateTime, ZipArchive};
+
+use crate::utils::{
+    error::Error, files::read_struct_from_json, types::MANIFEST_FILE_NAME,
+    zip_utils::main::Compression,
+};
+
+/// A single entry of the original archive
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    /// Name of the entry inside the archive, e.g. `word/document.xml`
+    pub name: String,
+    pub compression: Compression,
+    /// Last modified time as `YYYY-MM-DDTHH:MM:SS`, `None` if the archive doesn't have a valid one
+    pub last_modified: Option<String>,
+}
+
+/// The entries of the original archive in their original order
+///
+/// This is recorded next to the summary file on extraction so rezip can restore the order, compression and timestamps
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchiveManifest {
+    pub entries: Vec<ManifestEntry>,
+}
+
+impl ArchiveManifest {
+    /// Record the entries of an archive, folders are skipped as they're implied by the entry names
+    pub fn from_archive<R: Read + Seek>(
+        archive: &mut ZipArchive<R>,
+        path: &Path,
+    ) -> Result<Self, Error> {
+        let mut entries = Vec::with_capacity(archive.len());
+        for index in 0..archive.len() {
+            let entry = archive
+                .by_index_raw(index)
+                .map_err(|e| Error::zip(path, e))?;
+            if entry.is_dir() {
+                continue;
+            }
+
+            let compression = match entry.compression() {
+                CompressionMethod::Stored => Compression::Stored,
+                // Everything else is written back deflated, the original level isn't stored in the archive
+                _ => Compression::Deflated(None),
+            };
+
+            entries.push(ManifestEntry {
+                name: entry.name().to_string(),
+                compression,
+                last_modified: entry.last_modified().map(format_zip_date_time),
+            });
+        }
+
+        Ok(Self { entries })
+    }
+
+    /// Find the entry with the given name
+    pub fn get_entry(&self, name: &str) -> Option<&ManifestEntry> {
+        self.entries.iter().find(|entry| entry.name == name)
+    }
+}
+
+/// Read the manifest recorded next to the extracted folder, `None` if there is none
+///
+/// The manifest lives in the root folder, which is the parent of the extracted folder
+pub fn find_manifest_for_extracted_folder(
+    extracted_folder: &str,
+) -> Result<Option<ArchiveManifest>, Error> {
+    let manifest_path = match Path::new(extracted_folder).parent() {
+        Some(root_folder) => root_folder.join(MANIFEST_FILE_NAME),
+        None => return Ok(None),
+    };
+
+    if !manifest_path.is_file() {
+        return Ok(None);
+    }
+
+    read_struct_from_json(&manifest_path.to_string_lossy()).map(Some)
+}
+
+/// Format a zip timestamp as `YYYY-MM-DDTHH:MM:SS`
+pub fn format_zip_date_time(date_time: DateTime) -> String {
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+        date_time.year(),
+        date_time.month(),
+        date_time.day(),
+        date_time.hour(),
+        date_time.minute(),
+        date_time.second()
+    )
+}
+
+/// Parse a timestamp formatted by [`format_zip_date_time`], `None` if it's not valid
+pub fn parse_zip_date_time(value: &str) -> Option<DateTime> {
+    let (date, time) = value.split_once('T')?;
+    let mut date_parts = date.split('-');
+    let mut time_parts = time.split(':');
+
+    let year = date_parts.next()?.parse().ok()?;
+    let month = date_parts.next()?.parse().ok()?;
+    let day = date_parts.next()?.parse().ok()?;
+    let hour = time_parts.next()?.parse().ok()?;
+    let minute = time_parts.next()?.parse().ok()?;
+    let second = time_parts.next()?.parse().ok()?;
+
+    DateTime::from_date_and_time(year, month, day, hour, minute, second).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zip_date_time_round_trip() {
+        let date_time = DateTime::from_date_and_time(2024, 2, 29, 13, 45, 30).unwrap();
+        let formatted = format_zip_date_time(date_time);
+
+        assert_eq!(formatted, "2024-02-29T13:45:30");
+        assert_eq!(parse_zip_date_time(&formatted), Some(date_time));
+    }
+
+    #[test]
+    fn test_parse_zip_date_time_invalid() {
+        assert_eq!(parse_zip_date_time(""), None);
+        assert_eq!(parse_zip_date_time("2024-02-29"), None);
+        assert_eq!(parse_zip_date_time("2024-13-01T00:00:00"), None);
+        // Zip timestamps can't be before 1980
+        assert_eq!(parse_zip_date_time("1970-01-01T00:00:00"), None);
+    }
+}
@@ -1 +1,3 @@
 pub mod main;
+pub mod manifest;
+pub mod reextract;
@@ -0,0 +1,237 @@
+use std::{
+    collections::BTreeSet,
+    fs::{self, File},
+    path::Path,
+};
+use zip::ZipArchive;
+
+use crate::utils::{
+    error::Error,
+    files::{get_part_names_in_folder, write_struct_to_json},
+    ooxml_package::{OoxmlPackage, is_enclosed_part_name},
+    types::MANIFEST_FILE_NAME,
+    zip_utils::manifest::ArchiveManifest,
+};
+
+/// What [`reextract_zip`] did to the extracted folder
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ReextractResult {
+    /// The parts rewritten with their content in the packed file
+    pub updated_parts: Vec<String>,
+    /// The parts that are new in the packed file
+    pub added_parts: Vec<String>,
+    /// The parts that are no longer in the packed file
+    pub removed_parts: Vec<String>,
+    /// The parts changed both in the packed file and in the extracted folder, nothing is written when there is any
+    pub conflicts: Vec<String>,
+}
+
+impl ReextractResult {
+    /// Whether the extracted folder was written to
+    pub fn has_changes(&self) -> bool {
+        !(self.updated_parts.is_empty()
+            && self.added_parts.is_empty()
+            && self.removed_parts.is_empty())
+    }
+}
+
+/// Bring the changes of the packed file into its extracted folder, keeping the edits made in the folder
+///
+/// `base` is the package the folder was last in sync with, e.g. the packed file when it was extracted or rezipped. Each
+/// part is compared on the three sides: the packed file's version is taken when the folder's is still the base one, the
+/// folder's is kept when the packed file didn't change it. A part changed on both sides to different contents is a
+/// conflict, the folder is left untouched then so no unsaved edit is lost.
+///
+/// The manifest in the root folder is recorded again from the packed file, see [`ArchiveManifest`]. A part name that
+/// would point outside the extracted folder is an [`Error::InvalidPackage`], see [`is_enclosed_part_name`].
+pub fn reextract_zip(
+    base: &OoxmlPackage,
+    package: &OoxmlPackage,
+    extracted_folder: &str,
+) -> Result<ReextractResult, Error> {
+    let extracted_path = Path::new(extracted_folder);
+    for ooxml_package in [base, package] {
+        if let Some(part_name) = ooxml_package
+            .part_names()
+            .find(|part_name| !is_enclosed_part_name(part_name))
+        {
+            return Err(Error::invalid_package(
+                &ooxml_package.path,
+                format!("The part name {part_name} points outside the package"),
+            ));
+        }
+    }
+
+    let part_names: BTreeSet<String> = base
+        .part_names()
+        .chain(package.part_names())
+        .map(String::from)
+        .chain(get_part_names_in_folder(extracted_path)?)
+        .collect();
+
+    let mut result = ReextractResult::default();
+    let mut writes: Vec<(&str, Option<&[u8]>)> = Vec::new();
+    for part_name in &part_names {
+        let base_data = base.read_part(part_name);
+        let package_data = package.read_part(part_name);
+        let folder_data = fs::read(extracted_path.join(part_name)).ok();
+        let folder_data = folder_data.as_deref();
+
+        if package_data == folder_data || package_data == base_data {
+            // Already up to date, or only edited in the folder
+            continue;
+        }
+        if folder_data != base_data {
+            result.conflicts.push(part_name.clone());
+            continue;
+        }
+
+        match (folder_data, package_data) {
+            (Some(_), Some(_)) => result.updated_parts.push(part_name.clone()),
+            (None, Some(_)) => result.added_parts.push(part_name.clone()),
+            (_, None) => result.removed_parts.push(part_name.clone()),
+        }
+        writes.push((part_name, package_data));
+    }
+
+    if !result.conflicts.is_empty() {
+        result.updated_parts.clear();
+        result.added_parts.clear();
+        result.removed_parts.clear();
+        return Ok(result);
+    }
+
+    for (part_name, data) in writes {
+        let part_path = extracted_path.join(part_name);
+        match data {
+            Some(data) => {
+                if let Some(parent) = part_path.parent() {
+                    fs::create_dir_all(parent).map_err(|e| Error::io(parent, e))?;
+                }
+                fs::write(&part_path, data).map_err(|e| Error::io(&part_path, e))?;
+            }
+            None => fs::remove_file(&part_path).map_err(|e| Error::io(&part_path, e))?,
+        }
+    }
+
+    // The entry order and timestamps follow the packed file now
+    if let Some(root_folder) = extracted_path.parent() {
+        let file = File::open(&package.path).map_err(|e| Error::io(&package.path, e))?;
+        let mut archive = ZipArchive::new(file).map_err(|e| Error::zip(&package.path, e))?;
+        let manifest = ArchiveManifest::from_archive(&mut archive, &package.path)?;
+        write_struct_to_json(
+            &manifest,
+            &root_folder.join(MANIFEST_FILE_NAME).to_string_lossy(),
+        )?;
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::ooxml_package::build_test_package;
+    use tempfile::tempdir;
+
+    fn create_package(path: &Path, parts: &[(&str, &str)]) -> OoxmlPackage {
+        let package = build_test_package(path, parts);
+        package.save(path).unwrap();
+        package
+    }
+
+    #[test]
+    fn test_reextract_zip() {
+        let temp_dir = tempdir().unwrap();
+        let extracted_folder = temp_dir.path().join("sample/extracted");
+        fs::create_dir_all(extracted_folder.join("word")).unwrap();
+        let extracted_folder = extracted_folder.to_str().unwrap();
+
+        let base = create_package(
+            &temp_dir.path().join("base.docx"),
+            &[
+                ("word/document.xml", "base"),
+                ("word/styles.xml", "base"),
+                ("word/footer1.xml", "base"),
+            ],
+        );
+        // Only the styles were edited in the folder
+        for (name, data) in [
+            ("word/document.xml", "base"),
+            ("word/styles.xml", "folder"),
+            ("word/footer1.xml", "base"),
+        ] {
+            fs::write(Path::new(extracted_folder).join(name), data).unwrap();
+        }
+
+        // Word changed the document, added a header and removed the footer
+        let package_path = temp_dir.path().join("sample.docx");
+        let package = create_package(
+            &package_path,
+            &[
+                ("word/document.xml", "word"),
+                ("word/styles.xml", "base"),
+                ("word/header1.xml", "word"),
+            ],
+        );
+        let result = reextract_zip(&base, &package, extracted_folder).unwrap();
+
+        assert_eq!(
+            result,
+            ReextractResult {
+                updated_parts: vec!["word/document.xml".to_string()],
+                added_parts: vec!["word/header1.xml".to_string()],
+                removed_parts: vec!["word/footer1.xml".to_string()],
+                conflicts: Vec::new(),
+            }
+        );
+        let read = |name: &str| fs::read_to_string(Path::new(extracted_folder).join(name)).ok();
+        assert_eq!(read("word/document.xml").as_deref(), Some("word"));
+        assert_eq!(read("word/styles.xml").as_deref(), Some("folder"));
+        assert_eq!(read("word/header1.xml").as_deref(), Some("word"));
+        assert_eq!(read("word/footer1.xml"), None);
+        assert!(
+            temp_dir
+                .path()
+                .join("sample")
+                .join(MANIFEST_FILE_NAME)
+                .is_file()
+        );
+
+        // Both sides changed the styles, nothing is written
+        let base = package;
+        let package = create_package(
+            &package_path,
+            &[
+                ("word/document.xml", "word 2"),
+                ("word/styles.xml", "word"),
+                ("word/header1.xml", "word"),
+            ],
+        );
+        let result = reextract_zip(&base, &package, extracted_folder).unwrap();
+
+        assert_eq!(result.conflicts, vec!["word/styles.xml"]);
+        assert!(!result.has_changes());
+        assert_eq!(read("word/document.xml").as_deref(), Some("word"));
+    }
+
+    #[test]
+    fn test_reextract_zip_err_on_part_outside_folder() {
+        let temp_dir = tempdir().unwrap();
+        let extracted_folder = temp_dir.path().join("sample/extracted");
+        fs::create_dir_all(&extracted_folder).unwrap();
+        let extracted_folder = extracted_folder.to_str().unwrap();
+
+        let base = create_package(
+            &temp_dir.path().join("sample.docx"),
+            &[("word/document.xml", "base")],
+        );
+        let mut package = base.clone();
+        package.replace_part("../../pwned.txt", b"pwned".to_vec());
+
+        let result = reextract_zip(&base, &package, extracted_folder);
+
+        assert!(matches!(result, Err(Error::InvalidPackage { .. })));
+        assert!(!temp_dir.path().join("pwned.txt").exists());
+    }
+}